@@ -232,6 +232,7 @@ impl ToTokens for PostDecor {
             PostDecor::CompleteShell { f, .. } => quote!(complete_shell(#f)),
             PostDecor::DebugFallback { .. } => quote!(debug_fallback()),
             PostDecor::DisplayFallback { .. } => quote!(display_fallback()),
+            PostDecor::EnvGroup { .. } => quote!(env_group()),
             PostDecor::Fallback { value, .. } => quote!(fallback(#value)),
             PostDecor::FallbackWith { f, .. } => quote!(fallback_with(#f)),
             PostDecor::Last { .. } => quote!(last()),
@@ -299,6 +300,9 @@ pub(crate) enum PostDecor {
     DisplayFallback {
         span: Span,
     },
+    EnvGroup {
+        span: Span,
+    },
     Fallback {
         span: Span,
         value: Box<Expr>,
@@ -338,6 +342,7 @@ impl PostDecor {
             | Self::CompleteShell { span, .. }
             | Self::DebugFallback { span }
             | Self::DisplayFallback { span }
+            | Self::EnvGroup { span }
             | Self::Fallback { span, .. }
             | Self::Last { span }
             | Self::FallbackWith { span, .. }
@@ -512,6 +517,8 @@ impl PostDecor {
             Self::DebugFallback { span }
         } else if kw == "display_fallback" {
             Self::DisplayFallback { span }
+        } else if kw == "env_group" {
+            Self::EnvGroup { span }
         } else if kw == "fallback" {
             let value = parse_expr(input)?;
             Self::Fallback { span, value }
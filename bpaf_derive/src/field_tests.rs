@@ -85,6 +85,19 @@ fn short_long() {
     assert_eq!(input.to_token_stream().to_string(), output.to_string());
 }
 
+#[test]
+fn mixed_short_long_aliases() {
+    let input: NamedField = parse_quote! {
+        #[bpaf(long("alpha"), short('a'), long("alfa"), short, env("ALPHA"), env("ALFA"))]
+        beta: usize
+    };
+    let output = quote! {
+        ::bpaf::long("alpha").short('a').long("alfa").short('b').env("ALPHA").env("ALFA")
+            .argument::<usize>("ARG")
+    };
+    assert_eq!(input.to_token_stream().to_string(), output.to_string());
+}
+
 #[test]
 fn derive_fallback() {
     let input: NamedField = parse_quote! {
@@ -699,6 +712,18 @@ fn hide_and_group_help() {
     assert_eq!(input.to_token_stream().to_string(), output.to_string());
 }
 
+#[test]
+fn external_env_group() {
+    let input: NamedField = parse_quote! {
+        #[bpaf(external(db_config), env_group)]
+        db: DbConfig
+    };
+    let output = quote! {
+        db_config().env_group()
+    };
+    assert_eq!(input.to_token_stream().to_string(), output.to_string());
+}
+
 #[test]
 fn any_field_1() {
     let input: NamedField = parse_quote! {
@@ -30,6 +30,7 @@ pub(crate) struct OptionsCfg {
     pub(crate) usage: Option<Box<Expr>>,
     pub(crate) version: Option<Box<Expr>>,
     pub(crate) max_width: Option<Box<Expr>>,
+    pub(crate) name: Option<LitStr>,
     pub(crate) fallback_usage: bool,
 }
 
@@ -260,6 +261,15 @@ impl Parse for TopInfo {
             } else if kw == "max_width" {
                 let max_width = parse_arg(input)?;
                 with_options(&kw, options.as_mut(), |opt| opt.max_width = Some(max_width))?;
+            } else if kw == "name" {
+                let name = parse_arg(input)?;
+                if command.is_some() {
+                    return Err(Error::new_spanned(
+                        kw,
+                        "Subcommands are shown with their command names, `name` only makes sense with `options`",
+                    ));
+                }
+                with_options(&kw, options.as_mut(), |opt| opt.name = Some(name))?;
             } else if let Some(pd) = PostDecor::parse(input, &kw)? {
                 attrs.push(pd);
             } else {
@@ -198,6 +198,7 @@ impl ToTokens for Top {
                     footer,
                     header,
                     max_width,
+                    name: _,
                     fallback_usage,
                 } = options;
 
@@ -255,6 +256,7 @@ impl ToTokens for Top {
                     footer,
                     header,
                     max_width,
+                    name,
                     fallback_usage,
                 } = options;
                 let body = match cargo_helper {
@@ -273,6 +275,7 @@ impl ToTokens for Top {
                 let footer = footer.as_ref().map(|v| quote!(.footer(#v)));
                 let header = header.as_ref().map(|v| quote!(.header(#v)));
                 let max_width = max_width.as_ref().map(|v| quote!(.max_width(#v)));
+                let name = name.as_ref().map(|v| quote!(.name(#v)));
 
                 quote! {
                     #vis fn #generate() -> ::bpaf::OptionParser<#ty> {
@@ -288,6 +291,7 @@ impl ToTokens for Top {
                         #footer
                         #usage
                         #max_width
+                        #name
                     }
                 }
             }
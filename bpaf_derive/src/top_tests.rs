@@ -1272,6 +1272,55 @@ fn max_width() {
     assert_eq!(input.to_token_stream().to_string(), expected.to_string());
 }
 
+#[test]
+fn options_with_name() {
+    let input: Top = parse_quote! {
+        #[bpaf(options, name("mytool"))]
+        struct Opt {
+            verbose: bool,
+        }
+    };
+    let expected = quote! {
+        fn opt() -> ::bpaf::OptionParser<Opt> {
+            #[allow(unused_imports)]
+            use ::bpaf::Parser;
+            {
+                let verbose = ::bpaf::long("verbose").switch();
+                ::bpaf::construct!(Opt { verbose, })
+            }
+            .to_options()
+            .name("mytool")
+        }
+    };
+    assert_eq!(input.to_token_stream().to_string(), expected.to_string());
+}
+
+#[test]
+fn options_with_struct_guard() {
+    let input: Top = parse_quote! {
+        #[bpaf(options, guard(valid_area, "area is too big"), descr("Rectangle"))]
+        struct Rect {
+            width: u32,
+            height: u32,
+        }
+    };
+    let expected = quote! {
+        fn rect() -> ::bpaf::OptionParser<Rect> {
+            #[allow(unused_imports)]
+            use ::bpaf::Parser;
+            {
+                let width = ::bpaf::long("width").argument::<u32>("ARG");
+                let height = ::bpaf::long("height").argument::<u32>("ARG");
+                ::bpaf::construct!(Rect { width, height, })
+            }
+            .guard(valid_area, "area is too big")
+            .to_options()
+            .descr("Rectangle")
+        }
+    };
+    assert_eq!(input.to_token_stream().to_string(), expected.to_string());
+}
+
 #[test]
 fn custom_bpaf_path_options() {
     let input: Top = parse_quote! {
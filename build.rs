@@ -0,0 +1,20 @@
+//! Detects compiler features `bpaf` can use while keeping the minimal supported Rust version
+use std::{env, process::Command};
+
+fn main() {
+    println!("cargo:rerun-if-changed=build.rs");
+    println!("cargo:rustc-check-cfg=cfg(bpaf_is_terminal)");
+
+    let rustc = env::var_os("RUSTC").unwrap_or_else(|| "rustc".into());
+    let minor = Command::new(rustc)
+        .arg("--version")
+        .output()
+        .ok()
+        .and_then(|out| String::from_utf8(out.stdout).ok())
+        .and_then(|version| version.split('.').nth(1)?.parse::<u32>().ok());
+
+    // std::io::IsTerminal is available since 1.70
+    if minor.map_or(false, |minor| minor >= 70) {
+        println!("cargo:rustc-cfg=bpaf_is_terminal");
+    }
+}
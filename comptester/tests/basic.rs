@@ -0,0 +1,10 @@
+use comptester::*;
+use pretty_assertions::assert_eq;
+
+#[test]
+fn fallback_shown_for_metavar_zsh() {
+    let buf = zsh_comptest("basic --speed \t").unwrap();
+    let expected = "% basic --speed
+SPEED: Set speed [default: 42]";
+    assert_eq!(buf, expected);
+}
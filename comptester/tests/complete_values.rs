@@ -0,0 +1,25 @@
+use comptester::*;
+use pretty_assertions::assert_eq;
+
+#[test]
+fn cv_all_values_zsh() {
+    let buf = zsh_comptest("complete_values --format \t").unwrap();
+    let expected = "% complete_values --format
+FORMAT: Output format
+json    -- Single JSON document
+jsonl   -- One JSON document per line
+text    -- Human readable text";
+    assert_eq!(buf, expected);
+}
+
+#[test]
+fn cv_unique_prefix_zsh() {
+    let buf = zsh_comptest("complete_values --format t\t").unwrap();
+    assert_eq!(buf, "% complete_values --format text");
+}
+
+#[test]
+fn cv_unique_prefix_bash() {
+    let buf = bash_comptest("complete_values --format t\t").unwrap();
+    assert_eq!(buf, "% complete_values --format text");
+}
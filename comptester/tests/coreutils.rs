@@ -48,6 +48,17 @@ cat";
     assert_eq!(buf, expected);
 }
 
+#[test]
+fn descriptions_elvish() {
+    let buf = elvish_comptest("coreutils b\t").unwrap();
+    let expected = r"% coreutils b2sum
+ COMPLETING argument
+b2sum               Print or check BLAKE2 (512-bit) checksums.
+base32              Base32 encode or decode FILE, or standard input, to standard output.
+basename";
+    assert_eq!(buf, expected);
+}
+
 #[test]
 fn cat_zsh() {
     let buf = zsh_comptest("coreutils cat -- \t").unwrap();
@@ -68,6 +79,15 @@ build.rs  Cargo.toml  src/  tests/"
     );
 }
 
+#[test]
+fn cat_flags_fish() {
+    let buf = fish_comptest("coreutils cat --squ\t").unwrap();
+    assert_eq!(buf, "% coreutils cat --squeeze-blank");
+
+    let buf = fish_comptest("coreutils cat --show-all --squ\t").unwrap();
+    assert_eq!(buf, "% coreutils cat --show-all --squeeze-blank");
+}
+
 #[test]
 fn cat_bash() {
     let buf = bash_comptest("coreutils cat -- \t\t").unwrap();
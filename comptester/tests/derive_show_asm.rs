@@ -39,7 +39,7 @@ fn all_options_fish() {
     //    let buf = fish_comptest("derive_show_asm \t", true).unwrap();
     let buf = fish_comptest("derive_show_asm -\t").unwrap();
 
-    let expected = "% derive_show_asm --att
+    let expected = "% derive_show_asm -
 --att                        (Generate assembly using AT&T style)
 --bench                           (Show results from a benchmark)
 --bin                                (Show results from a binary)
@@ -54,10 +54,68 @@ fn all_options_fish() {
 --manifest-path                              (Path to Cargo.toml)
 --no-color                           (Disable color highlighting)
 --offline                     (Run without accessing the network)
---package                            (Package to use if ambigous)
 --rust                              (Print interleaved Rust code)
 --target-dir    (Custom target directory for generated artifacts)
---test                                 (Show results from a test)";
+--test                                 (Show results from a test)
+-p                                   (Package to use if ambigous)";
+    assert_eq!(buf, expected);
+}
+
+#[test]
+fn single_dash_zsh() {
+    let buf = zsh_comptest("derive_show_asm -\t").unwrap();
+
+    let expected = r"% derive_show_asm -
+--manifest-path=PATH     -- Path to Cargo.toml
+--target-dir=DIR         -- Custom target directory for generated artifacts
+-p=SPEC                  -- Package to use if ambigous
+--dry                    -- Produce a build plan instead of actually building
+--frozen                 -- Requires Cargo.lock and cache are up to date
+--locked                 -- Requires Cargo.lock is up to date
+--offline                -- Run without accessing the network
+Select artifact to use for analysis
+--lib                    -- Show results from library code
+--test=TEST              -- Show results from a test
+--bench=BENCH            -- Show results from a benchmark
+--example=EXAMPLE        -- Show results from an example
+--bin=BIN                -- Show results from a binary
+How to render output
+--rust                   -- Print interleaved Rust code
+--color                  -- Enable color highlighting
+--no-color               -- Disable color highlighting
+--full-name              -- include full demangled name instead of just prefix
+Pick output type
+--intel                  -- Generate assembly using Intel style
+--att                    -- Generate assembly using AT&T style";
+    assert_eq!(buf, expected);
+}
+
+#[test]
+fn double_dash_zsh() {
+    let buf = zsh_comptest("derive_show_asm --\t").unwrap();
+
+    let expected = r"% derive_show_asm --
+--manifest-path=PATH     -- Path to Cargo.toml
+--target-dir=DIR         -- Custom target directory for generated artifacts
+--package=SPEC           -- Package to use if ambigous
+--dry                    -- Produce a build plan instead of actually building
+--frozen                 -- Requires Cargo.lock and cache are up to date
+--locked                 -- Requires Cargo.lock is up to date
+--offline                -- Run without accessing the network
+Select artifact to use for analysis
+--lib                    -- Show results from library code
+--test=TEST              -- Show results from a test
+--bench=BENCH            -- Show results from a benchmark
+--example=EXAMPLE        -- Show results from an example
+--bin=BIN                -- Show results from a binary
+How to render output
+--rust                   -- Print interleaved Rust code
+--color                  -- Enable color highlighting
+--no-color               -- Disable color highlighting
+--full-name              -- include full demangled name instead of just prefix
+Pick output type
+--intel                  -- Generate assembly using Intel style
+--att                    -- Generate assembly using AT&T style";
     assert_eq!(buf, expected);
 }
 
@@ -165,6 +223,34 @@ FUNCTION: Complete or partial function name to filter";
     assert_eq!(buf, expected);
 }
 
+#[test]
+fn single_dash_bash() {
+    let buf = bash_comptest("derive_show_asm -\t\t").unwrap();
+    let expected = r"% derive_show_asm -
+--manifest-path=PATH     -- Path to Cargo.toml
+--target-dir=DIR         -- Custom target directory for generated artifacts
+-p=SPEC                  -- Package to use if ambigous
+Select artifact to use for analysis
+--lib                    -- Show results from library code
+--test=TEST              -- Show results from a test
+--bench=BENCH            -- Show results from a benchmark
+--example=EXAMPLE        -- Show results from an example
+--bin=BIN                -- Show results from a binary
+--dry                    -- Produce a build plan instead of actually building
+--frozen                 -- Requires Cargo.lock and cache are up to date
+--locked                 -- Requires Cargo.lock is up to date
+--offline                -- Run without accessing the network
+How to render output
+--rust                   -- Print interleaved Rust code
+--color                  -- Enable color highlighting
+--no-color               -- Disable color highlighting
+--full-name              -- include full demangled name instead of just prefix
+Pick output type
+--intel                  -- Generate assembly using Intel style
+--att                    -- Generate assembly using AT&T style";
+    assert_eq!(buf, expected);
+}
+
 #[test]
 fn bash_file_completion() {
     let buf = bash_comptest("derive_show_asm --manifest-path \t\t").unwrap();
@@ -0,0 +1,30 @@
+use comptester::*;
+use pretty_assertions::assert_eq;
+
+#[test]
+fn value_variants_zsh() {
+    let buf = zsh_comptest("exclude --exclude --ver\t").unwrap();
+    let expected = "% exclude --exclude --ver
+PATTERN: Pattern to exclude, can be used multiple times
+--verbose                -- Lines with --verbose flag
+--version                -- Lines with --version flag";
+    assert_eq!(buf, expected);
+}
+
+#[test]
+fn single_value_zsh() {
+    let buf = zsh_comptest("exclude --exclude --verb\t").unwrap();
+    assert_eq!(buf, "% exclude --exclude --verbose");
+}
+
+#[test]
+fn single_value_bash() {
+    let buf = bash_comptest("exclude --exclude --verb\t").unwrap();
+    assert_eq!(buf, "% exclude --exclude --verbose");
+}
+
+#[test]
+fn flag_after_value_bash() {
+    let buf = bash_comptest("exclude --exclude target --verb\t").unwrap();
+    assert_eq!(buf, "% exclude --exclude target --verbose");
+}
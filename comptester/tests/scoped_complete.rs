@@ -0,0 +1,37 @@
+use comptester::*;
+use pretty_assertions::assert_eq;
+
+#[test]
+fn build_target_zsh() {
+    let buf = zsh_comptest("scoped_complete build --target \t").unwrap();
+    let expected = "% scoped_complete build --target
+TARGET: Target triple to build for
+x86_64-unknown-linux-gnu
+wasm32-unknown-unknown";
+    assert_eq!(buf, expected);
+}
+
+#[test]
+fn run_target_zsh() {
+    let buf = zsh_comptest("scoped_complete run --target \t").unwrap();
+    let expected = "% scoped_complete run --target
+TARGET: Binary to run
+server
+worker";
+    assert_eq!(buf, expected);
+}
+
+#[test]
+fn build_target_bash() {
+    let buf = bash_comptest("scoped_complete build --target w\t").unwrap();
+    assert_eq!(
+        buf,
+        "% scoped_complete build --target wasm32-unknown-unknown"
+    );
+}
+
+#[test]
+fn run_target_bash() {
+    let buf = bash_comptest("scoped_complete run --target w\t").unwrap();
+    assert_eq!(buf, "% scoped_complete run --target worker");
+}
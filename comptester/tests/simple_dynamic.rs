@@ -21,3 +21,27 @@ uggo                     -- CLI tool to query builds from u.gg";
     //    let buf = zsh_comptest("simple_dynamic ?").unwrap();
     //    todo!("\n{}", buf);
 }
+
+#[test]
+fn sd_help_flag_zsh() {
+    let buf = zsh_comptest("simple_dynamic --he\t").unwrap();
+    assert_eq!(buf, "% simple_dynamic --help");
+}
+
+#[test]
+fn sd_help_flag_bash() {
+    let buf = bash_comptest("simple_dynamic --he\t").unwrap();
+    assert_eq!(buf, "% simple_dynamic --help");
+}
+
+#[test]
+fn sd_attached_value_bash() {
+    let buf = bash_comptest("simple_dynamic --crate=cargo-sh\t").unwrap();
+    assert_eq!(buf, "% simple_dynamic --crate=cargo-show-asm");
+}
+
+#[test]
+fn sd_attached_value_zsh() {
+    let buf = zsh_comptest("simple_dynamic --crate=cargo-sh\t").unwrap();
+    assert_eq!(buf, "% simple_dynamic --crate=cargo-show-asm");
+}
@@ -102,7 +102,7 @@ $ app {all_args}<br>
 ",
             buf.render_html(full, true)
         )?,
-        Err(ParseFailure::Stderr(buf)) => writeln!(
+        Err(ParseFailure::Stderr(buf, _)) => writeln!(
             res,
             "
 <div class='bpaf-doc'>
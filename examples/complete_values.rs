@@ -0,0 +1,16 @@
+//! Argument restricted to a fixed set of values, completed with descriptions
+
+use bpaf::{batteries::complete_values, *};
+
+fn main() {
+    let format = complete_values(
+        long("format").help("Output format"),
+        "FORMAT",
+        [
+            ("json", "Single JSON document"),
+            ("jsonl", "One JSON document per line"),
+            ("text", "Human readable text"),
+        ],
+    );
+    println!("{:?}", format.to_options().run());
+}
@@ -0,0 +1,38 @@
+//! Completing values for an argument when values can look like flags
+//!
+//! With `allow_hyphen_values` `--exclude --verbose` passes `--verbose` as a value to
+//! `--exclude` and completion offers values for `--exclude` even if user started typing a dash
+
+use bpaf::*;
+
+fn patterns(input: &String) -> Vec<(&'static str, Option<&'static str>)> {
+    let patterns = [
+        ("--verbose", "Lines with --verbose flag"),
+        ("--version", "Lines with --version flag"),
+        ("target", "Build artifacts"),
+    ];
+    patterns
+        .iter()
+        .filter(|p| p.0.starts_with(input))
+        .map(|p| (p.0, Some(p.1)))
+        .collect()
+}
+
+#[derive(Debug, Clone)]
+#[allow(dead_code)]
+struct Options {
+    exclude: Vec<String>,
+    verbose: bool,
+}
+
+fn main() {
+    let exclude = long("exclude")
+        .help("Pattern to exclude, can be used multiple times")
+        .argument::<String>("PATTERN")
+        .allow_hyphen_values()
+        .complete(patterns)
+        .many();
+    let verbose = long("verbose").help("Produce verbose output").switch();
+    let opts = construct!(Options { exclude, verbose }).to_options().run();
+    println!("{:?}", opts);
+}
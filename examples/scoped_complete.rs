@@ -0,0 +1,50 @@
+//! Two commands with a same-named argument and different completion functions
+
+#![allow(dead_code, clippy::ptr_arg)]
+
+use bpaf::*;
+
+fn build_targets(input: &String) -> Vec<(&'static str, Option<&'static str>)> {
+    ["x86_64-unknown-linux-gnu", "wasm32-unknown-unknown"]
+        .iter()
+        .filter(|t| t.starts_with(input.as_str()))
+        .map(|t| (*t, None))
+        .collect()
+}
+
+fn run_targets(input: &String) -> Vec<(&'static str, Option<&'static str>)> {
+    ["server", "worker"]
+        .iter()
+        .filter(|t| t.starts_with(input.as_str()))
+        .map(|t| (*t, None))
+        .collect()
+}
+
+#[derive(Debug, Clone)]
+enum Cmd {
+    Build { target: String },
+    Run { target: String },
+}
+
+fn main() {
+    let target = long("target")
+        .help("Target triple to build for")
+        .argument::<String>("TARGET")
+        .complete(build_targets);
+    let build = construct!(Cmd::Build { target })
+        .to_options()
+        .descr("Build the project")
+        .command("build");
+
+    let target = long("target")
+        .help("Binary to run")
+        .argument::<String>("TARGET")
+        .complete(run_targets);
+    let run = construct!(Cmd::Run { target })
+        .to_options()
+        .descr("Run a binary")
+        .command("run");
+
+    let opts = construct!([build, run]).to_options().run();
+    println!("{:?}", opts);
+}
@@ -1392,6 +1392,8 @@
                 //! 4. `long` or `short` with a parameter uses that instead
                 //! 5. You can have multiple `long` and `short` annotations, the first of each type becomes a
                 //!    visible name, remaining are used as hidden aliases
+                //! 6. Order only matters within each kind: `short('a'), long("alpha"), short('b')` uses `-a` and
+                //!    `--alpha` as visible names and `-b` as a hidden alias, same applies to multiple `env`
                 //! 
                 //! And if you decide to add names - they should go to the left side of the annotation list
                 //!
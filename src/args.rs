@@ -34,9 +34,11 @@ use crate::{
 /// ```
 pub struct Args<'a> {
     items: Box<dyn ExactSizeIterator<Item = OsString> + 'a>,
-    name: Option<String>,
+    pub(crate) name: Option<String>,
     #[cfg(feature = "autocomplete")]
     c_rev: Option<usize>,
+    /// number of items added with [`prepend`](Args::prepend)
+    prepended: usize,
 }
 
 impl Args<'_> {
@@ -91,6 +93,7 @@ impl<const N: usize> From<&'static [&'static str; N]> for Args<'_> {
             #[cfg(feature = "autocomplete")]
             c_rev: None,
             name: None,
+            prepended: 0,
         }
     }
 }
@@ -102,6 +105,7 @@ impl<'a> From<&'a [&'a std::ffi::OsStr]> for Args<'a> {
             #[cfg(feature = "autocomplete")]
             c_rev: None,
             name: None,
+            prepended: 0,
         }
     }
 }
@@ -113,6 +117,7 @@ impl<'a> From<&'a [&'a str]> for Args<'a> {
             #[cfg(feature = "autocomplete")]
             c_rev: None,
             name: None,
+            prepended: 0,
         }
     }
 }
@@ -124,6 +129,7 @@ impl<'a> From<&'a [String]> for Args<'a> {
             #[cfg(feature = "autocomplete")]
             c_rev: None,
             name: None,
+            prepended: 0,
         }
     }
 }
@@ -135,11 +141,25 @@ impl<'a> From<&'a [OsString]> for Args<'a> {
             #[cfg(feature = "autocomplete")]
             c_rev: None,
             name: None,
+            prepended: 0,
         }
     }
 }
 
 impl Args<'_> {
+    /// Put extra items in front of the command line
+    ///
+    /// `--` among extra items only affects extra items, see [`State::construct`]
+    pub(crate) fn prepend(&mut self, extra: &[OsString]) {
+        if extra.is_empty() {
+            return;
+        }
+        let items = std::mem::replace(&mut self.items, Box::new(std::iter::empty()));
+        let items = extra.iter().cloned().chain(items).collect::<Vec<_>>();
+        self.items = Box::new(items.into_iter());
+        self.prepended += extra.len();
+    }
+
     /// Get a list of command line arguments from OS
     #[must_use]
     pub fn current_args() -> Self {
@@ -155,6 +175,7 @@ impl Args<'_> {
             #[cfg(feature = "autocomplete")]
             c_rev: None,
             name,
+            prepended: 0,
         }
     }
 }
@@ -193,7 +214,8 @@ fn disambiguate_short(
     items: &mut Vec<Arg>,
 ) -> Option<Message> {
     // block can start with 0 or more short flags
-    // followed by zero or one short argument, possibly with a body
+    // followed by zero or one short argument, possibly with a body,
+    // body can be separated with = the same way as in -f=body
 
     // keep the old length around so we can trimp items to it and push a Arg::Word
     // if we decide to give up
@@ -222,7 +244,8 @@ fn disambiguate_short(
                 let adjacent_body = !rest.is_empty();
                 items.push(Arg::Short(c, adjacent_body, std::mem::take(&mut os)));
                 if adjacent_body {
-                    items.push(Arg::Word(rest.into()));
+                    let body = rest.strip_prefix('=').unwrap_or(rest);
+                    items.push(Arg::Word(body.into()));
                 }
                 return None;
             }
@@ -273,6 +296,14 @@ mod inner {
         /// path to current command, "deeper" parser should win in or_else branches
         pub(crate) path: Vec<String>,
 
+        /// Command to run if command line contains none of the commands, set by
+        /// [`OptionParser`](crate::OptionParser) for its immediate children
+        pub(crate) default_command: Option<&'static str>,
+
+        /// Parsers marked with [`global`](crate::Parser::global) by the parent parsers,
+        /// subcommands include them in help and completion
+        pub(crate) globals: Vec<crate::structs::GlobalItem>,
+
         #[cfg(feature = "autocomplete")]
         comp: Option<crate::complete_gen::Complete>,
 
@@ -294,6 +325,14 @@ mod inner {
         pub(crate) fn depth(&self) -> usize {
             self.path.len()
         }
+
+        /// Replace the program name used in usage lines and help messages
+        pub(crate) fn set_name(&mut self, name: &str) {
+            match self.path.first_mut() {
+                Some(first) => *first = name.to_owned(),
+                None => self.path.push(name.to_owned()),
+            }
+        }
     }
 
     pub(crate) struct ArgsIter<'a> {
@@ -323,7 +362,8 @@ mod inner {
         ) -> State {
             let mut items = Vec::new();
             let mut pos_only = false;
-            let mut double_dash_marker = None;
+            let mut double_dash_markers = Vec::new();
+            let prepended = args.prepended;
 
             #[cfg(feature = "autocomplete")]
             let mut comp_scanner = crate::complete_run::ArgScanner {
@@ -331,7 +371,11 @@ mod inner {
                 name: args.name.as_deref(),
             };
 
-            for os in args.items {
+            for (input_ix, os) in args.items.enumerate() {
+                // "--" in prepended items doesn't change how the real command line is parsed
+                if input_ix == prepended {
+                    pos_only = false;
+                }
                 if pos_only {
                     items.push(Arg::PosWord(os));
                     continue;
@@ -358,7 +402,28 @@ mod inner {
                     }
                     Some((ArgType::Short, short, Some(arg))) => {
                         let mut chars = short.chars();
-                        items.push(Arg::Short(chars.next().unwrap(), true, os));
+                        let first = chars.next().unwrap();
+                        // -xvf=value - a block of flags followed by an argument with a body
+                        if short_flags.contains(&first) && !short_args.contains(&first) {
+                            if let Some(block) = os
+                                .to_str()
+                                .filter(|s| !s[1 + first.len_utf8()..].starts_with('='))
+                                .map(|s| s[1..].to_owned())
+                            {
+                                if let Some(msg) = super::disambiguate_short(
+                                    os,
+                                    block,
+                                    short_flags,
+                                    short_args,
+                                    &mut items,
+                                ) {
+                                    *err = Some(msg);
+                                    break;
+                                }
+                                continue;
+                            }
+                        }
+                        items.push(Arg::Short(first, true, os));
                         items.push(arg);
                     }
                     // --key and --key=val
@@ -372,7 +437,7 @@ mod inner {
                     // handle "--" specifically as "end of flags" marker
                     None => {
                         if os == "--" {
-                            double_dash_marker = Some(items.len());
+                            double_dash_markers.push(items.len());
                             pos_only = true;
                         }
                         items.push(if pos_only {
@@ -386,7 +451,7 @@ mod inner {
 
             let mut item_state = vec![ItemState::Unparsed; items.len()];
             let mut remaining = items.len();
-            if let Some(ix) = double_dash_marker {
+            for &ix in &double_dash_markers {
                 item_state[ix] = ItemState::Parsed;
                 remaining -= 1;
 
@@ -412,6 +477,8 @@ mod inner {
                 items: items.into(),
                 current: None,
                 path,
+                default_command: None,
+                globals: Vec::new(),
                 #[cfg(feature = "autocomplete")]
                 comp,
             }
@@ -477,6 +544,24 @@ mod inner {
             self.remaining
         }
 
+        /// Check if parsers would see the same input in both states: same command line,
+        /// same items consumed, same scope and same command path
+        pub(crate) fn same_input(&self, other: &State) -> bool {
+            Rc::ptr_eq(&self.items, &other.items)
+                && self.item_state == other.item_state
+                && self.scope == other.scope
+                && self.current == other.current
+                && self.path == other.path
+        }
+
+        /// Mark the same items as consumed as in `after`, a state that was produced from one
+        /// with the same input, see [`same_input`](State::same_input)
+        pub(crate) fn replay_consumed(&mut self, after: &State) {
+            self.item_state.clone_from(&after.item_state);
+            self.remaining = after.remaining;
+            self.current = after.current;
+        }
+
         /// Get an argument from a scope that was not consumed yet
         pub(crate) fn get(&self, ix: usize) -> Option<&Arg> {
             if self.scope.contains(&ix) && self.item_state.get(ix)?.present() {
@@ -667,10 +752,12 @@ impl State {
     ///
     /// Returns Ok(None) if flag isn't present
     /// Returns Err if flag is present but value is either missing or strange.
+    /// With `hyphen_values` a word that looks like a flag right after the name is the value.
     pub(crate) fn take_arg(
         &mut self,
         named: &NamedArg,
         adjacent: bool,
+        hyphen_values: bool,
         metavar: Metavar,
     ) -> Result<Option<OsString>, Error> {
         let (key_ix, _arg) = match self
@@ -684,6 +771,7 @@ impl State {
         let val_ix = key_ix + 1;
         let val = match self.get(val_ix) {
             Some(Arg::Word(w) | Arg::ArgWord(w)) => w,
+            Some(Arg::Short(_, false, w) | Arg::Long(_, false, w)) if hyphen_values => w,
             _ => return Err(Error(Message::NoArgument(key_ix, metavar))),
         };
         let val = val.clone();
@@ -693,6 +781,68 @@ impl State {
         Ok(Some(val))
     }
 
+    /// get a short or long flag followed by everything after it, joined with spaces
+    ///
+    /// Returns Ok(None) if flag isn't present
+    /// Returns Err if flag is present but nothing follows it
+    pub(crate) fn take_rest(
+        &mut self,
+        named: &NamedArg,
+        metavar: Metavar,
+    ) -> Result<Option<OsString>, Error> {
+        let key_ix = match self
+            .items_iter()
+            .find(|arg| named.matches_arg(arg.1, false))
+        {
+            Some((ix, _)) => ix,
+            None => return Ok(None),
+        };
+
+        // `--` is consumed by bpaf when the State is created, but once the tail
+        // started it is a part of the value
+        let mut tail = Vec::new();
+        for ix in key_ix + 1..self.scope().end {
+            let double_dash =
+                !tail.is_empty() && matches!(&self.items[ix], Arg::PosWord(w) if w == "--");
+            if self.get(ix).is_some() || double_dash {
+                tail.push(ix);
+            }
+        }
+        if tail.is_empty() {
+            return Err(Error(Message::NoArgument(key_ix, metavar)));
+        }
+
+        let mut res = OsString::new();
+        for &ix in &tail {
+            // items split from a single word by bpaf share the original text,
+            // only the first one of them is used
+            let prev = if ix - 1 > key_ix {
+                self.items.get(ix - 1)
+            } else {
+                None
+            };
+            let word = match (&self.items[ix], prev) {
+                (_, Some(Arg::Short(_, true, _) | Arg::Long(_, true, _))) => continue,
+                (Arg::Short(_, true, os), Some(Arg::Short(_, false, prev))) if os == prev => {
+                    continue
+                }
+                (Arg::Short(_, _, os), _) if os.is_empty() => continue,
+                (arg, _) => arg.os_str(),
+            };
+            if !res.is_empty() {
+                res.push(" ");
+            }
+            res.push(word);
+        }
+
+        self.current = Some(key_ix);
+        self.remove(key_ix);
+        for ix in tail {
+            self.remove(ix);
+        }
+        Ok(Some(res))
+    }
+
     /// gets first positional argument present
     ///
     /// returns Ok(None) if input is empty
@@ -753,7 +903,7 @@ mod tests {
     use super::*;
     use crate::meta_help::Metavar;
     use crate::{long, short};
-    const M: Metavar = Metavar("M");
+    const M: Metavar = Metavar(std::borrow::Cow::Borrowed("M"));
 
     #[allow(clippy::fallible_impl_from)] // this is for tests only, panic is okay
     impl<const N: usize> From<&'static [&'static str; N]> for State {
@@ -771,7 +921,10 @@ mod tests {
     #[test]
     fn long_arg() {
         let mut a = State::from(&["--speed", "12"]);
-        let s = a.take_arg(&long("speed"), false, M).unwrap().unwrap();
+        let s = a
+            .take_arg(&long("speed"), false, false, M)
+            .unwrap()
+            .unwrap();
         assert_eq!(s, "12");
         assert!(a.is_empty());
     }
@@ -801,7 +954,10 @@ mod tests {
     #[test]
     fn long_arg_with_equality() {
         let mut a = State::from(&["--speed=12"]);
-        let s = a.take_arg(&long("speed"), false, M).unwrap().unwrap();
+        let s = a
+            .take_arg(&long("speed"), false, false, M)
+            .unwrap()
+            .unwrap();
         assert_eq!(s, "12");
         assert!(a.is_empty());
     }
@@ -809,7 +965,7 @@ mod tests {
     #[test]
     fn long_arg_with_equality_and_minus() {
         let mut a = State::from(&["--speed=-12"]);
-        let s = a.take_arg(&long("speed"), true, M).unwrap().unwrap();
+        let s = a.take_arg(&long("speed"), true, false, M).unwrap().unwrap();
         assert_eq!(s, "-12");
         assert!(a.is_empty());
     }
@@ -817,7 +973,7 @@ mod tests {
     #[test]
     fn short_arg_with_equality() {
         let mut a = State::from(&["-s=12"]);
-        let s = a.take_arg(&short('s'), false, M).unwrap().unwrap();
+        let s = a.take_arg(&short('s'), false, false, M).unwrap().unwrap();
         assert_eq!(s, "12");
         assert!(a.is_empty());
     }
@@ -825,7 +981,7 @@ mod tests {
     #[test]
     fn short_arg_with_equality_and_minus() {
         let mut a = State::from(&["-s=-12"]);
-        let s = a.take_arg(&short('s'), false, M).unwrap().unwrap();
+        let s = a.take_arg(&short('s'), false, false, M).unwrap().unwrap();
         assert_eq!(s, "-12");
         assert!(a.is_empty());
     }
@@ -833,7 +989,7 @@ mod tests {
     #[test]
     fn short_arg_with_equality_and_minus_is_adjacent() {
         let mut a = State::from(&["-s=-12"]);
-        let s = a.take_arg(&short('s'), true, M).unwrap().unwrap();
+        let s = a.take_arg(&short('s'), true, false, M).unwrap().unwrap();
         assert_eq!(s, "-12");
         assert!(a.is_empty());
     }
@@ -841,7 +997,7 @@ mod tests {
     #[test]
     fn short_arg_without_equality() {
         let mut a = State::from(&["-s", "12"]);
-        let s = a.take_arg(&short('s'), false, M).unwrap().unwrap();
+        let s = a.take_arg(&short('s'), false, false, M).unwrap().unwrap();
         assert_eq!(s, "12");
         assert!(a.is_empty());
     }
@@ -868,7 +1024,7 @@ mod tests {
     fn command_with_flags() {
         let mut a = State::from(&["cmd", "-s", "v"]);
         assert!(a.take_cmd("cmd"));
-        let s = a.take_arg(&short('s'), false, M).unwrap().unwrap();
+        let s = a.take_arg(&short('s'), false, false, M).unwrap().unwrap();
         assert_eq!(s, "v");
         assert!(a.is_empty());
     }
@@ -903,7 +1059,7 @@ mod tests {
     #[test]
     fn positionals_after_double_dash3() {
         let mut a = State::from(&["-v", "12", "--", "-x"]);
-        let w = a.take_arg(&short('v'), false, M).unwrap().unwrap();
+        let w = a.take_arg(&short('v'), false, false, M).unwrap().unwrap();
         assert_eq!(w, "12");
         let w = a.take_positional_word(M).unwrap();
         assert_eq!(w.2, "-x");
@@ -927,7 +1083,7 @@ mod tests {
         let mut err = None;
         let mut a = State::construct(args, &[], &['a'], &mut err);
 
-        let r = a.take_arg(&short('a'), false, M).unwrap().unwrap();
+        let r = a.take_arg(&short('a'), false, false, M).unwrap().unwrap();
         assert_eq!(r, "bc");
     }
 
@@ -8,7 +8,10 @@
 //! Examples contain combinatoric usage, for derive usage you should create a parser function and
 //! use `external` annotation.
 
-use crate::{construct, literal, parsers::NamedArg, short, Parser};
+use crate::{
+    any, choice, construct, doc::Style, literal, long, parsers::NamedArg, positional, short, Parser,
+};
+use std::{ffi::OsString, ops::RangeInclusive, str::FromStr};
 
 /// `--verbose` and `--quiet` flags with results encoded as number
 ///
@@ -136,6 +139,513 @@ pub fn toggle_flag<T: Copy + 'static>(
     construct!([a, b]).many().map(|xs| xs.into_iter().last())
 }
 
+/// Several long flags that map to the values of a single enum
+///
+/// Takes a list of long flag names and corresponding values, creates a
+/// [`req_flag`](NamedArg::req_flag) for every pair and combines them as mutually exclusive
+/// alternatives. User must pass exactly one of the flags, passing several of them is a conflict.
+/// To make the choice optional use [`fallback`](Parser::fallback) or
+/// [`optional`](Parser::optional). For flags with help messages or short names use
+/// `req_flag` and [`construct!`] directly.
+///
+/// # Usage
+/// ```rust
+/// # use bpaf::*;
+/// use bpaf::batteries::enum_flags;
+///
+/// #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// enum Size {
+///     Small,
+///     Medium,
+///     Large,
+/// }
+///
+/// fn size() -> impl Parser<Size> {
+///     enum_flags([
+///         ("small", Size::Small),
+///         ("medium", Size::Medium),
+///         ("large", Size::Large),
+///     ])
+///     .fallback(Size::Medium)
+/// }
+/// # let parser = size().to_options();
+/// # let res = parser.run_inner(&[]).unwrap();
+/// # assert_eq!(res, Size::Medium);
+/// # let res = parser.run_inner(&["--large"]).unwrap();
+/// # assert_eq!(res, Size::Large);
+/// ```
+#[must_use]
+pub fn enum_flags<T, const N: usize>(flags: [(&'static str, T); N]) -> impl Parser<T>
+where
+    T: Clone + 'static,
+{
+    choice(IntoIterator::into_iter(flags).map(|(name, value)| long(name).req_flag(value).boxed()))
+}
+
+/// Parse a group of items that must be present either all together or not at all
+///
+/// Takes a tuple of parsers and succeeds with `Some` of a tuple with all the values if all of
+/// them succeed or with `None` if none of them produce a value. If only some of the parsers
+/// succeed - `all_or_none` fails with `message`, so the message should explain which items go
+/// together.
+///
+/// Compared to making a structure from those parsers and making it
+/// [`optional`](Parser::optional) this gives a clear error message when user passes only some
+/// of the values instead of complaining about the first missing item.
+///
+/// An item counts as present when its parser produces a value, so parsers that always produce
+/// one, such as a [`switch`](NamedArg::switch) or anything with a
+/// [`fallback`](Parser::fallback), are always present: with such a member `all_or_none` never
+/// returns `None` and fails unless all the other items are given as well.
+///
+/// Usage and help list the items the same way as for a structure made of
+/// [`optional`](Parser::optional) items: `[--db-host=HOST] [--db-port=PORT]`.
+///
+/// Implemented for tuples of up to 6 parsers.
+///
+/// # Usage
+/// ```rust
+/// # use bpaf::*;
+/// use bpaf::batteries::all_or_none;
+///
+/// fn database() -> impl Parser<Option<(String, u16)>> {
+///     let host = long("db-host").argument::<String>("HOST");
+///     let port = long("db-port").argument::<u16>("PORT");
+///     all_or_none((host, port), "if --db-host is given, --db-port is also required")
+/// }
+/// # let parser = database().to_options();
+/// # let res = parser.run_inner(&[]).unwrap();
+/// # assert_eq!(res, None);
+/// # let res = parser.run_inner(&["--db-host", "localhost", "--db-port", "5432"]).unwrap();
+/// # assert_eq!(res, Some((String::from("localhost"), 5432)));
+/// # let res = parser.run_inner(&["--db-host", "localhost"]).unwrap_err().unwrap_stderr();
+/// # assert_eq!(res, "if --db-host is given, --db-port is also required");
+/// ```
+#[must_use]
+pub fn all_or_none<T, P>(parsers: P, message: &'static str) -> impl Parser<Option<T>>
+where
+    P: AllOrNone<T>,
+{
+    parsers.all_or_none(message)
+}
+
+/// A tuple of parsers that can be used with [`all_or_none`]
+pub trait AllOrNone<T> {
+    #[doc(hidden)]
+    fn all_or_none(self, message: &'static str) -> Box<dyn Parser<Option<T>>>;
+}
+
+macro_rules! all_or_none_tuple {
+    ($($p:ident $t:ident),+) => {
+        impl<$($p, $t),+> AllOrNone<($($t,)+)> for ($($p,)+)
+        where
+            $($p: Parser<$t> + 'static, $t: 'static,)+
+        {
+            #[allow(non_snake_case)]
+            fn all_or_none(self, message: &'static str) -> Box<dyn Parser<Option<($($t,)+)>>> {
+                let ($($p,)+) = self;
+                $(let $p = $p.optional();)+
+                construct!($($p),+)
+                    .parse_exact(move |($($p,)+)| {
+                        let any = [$($p.is_some()),+].iter().any(|p| *p);
+                        match ($($p,)+) {
+                            ($(Some($p),)+) => Ok(Some(($($p,)+))),
+                            _ if any => Err(message),
+                            _ => Ok(None),
+                        }
+                    })
+                    .boxed()
+            }
+        }
+    };
+}
+
+all_or_none_tuple!(PA A, PB B);
+all_or_none_tuple!(PA A, PB B, PC C);
+all_or_none_tuple!(PA A, PB B, PC C, PD D);
+all_or_none_tuple!(PA A, PB B, PC C, PD D, PE E);
+all_or_none_tuple!(PA A, PB B, PC C, PD D, PE E, PF F);
+
+/// Collect all the remaining positional items for forwarding to another program
+///
+/// Parser consumes every positional item left on the command line, both before and after `--`,
+/// and is hidden from `--help`, usage line and shell completion. This is useful for thin
+/// wrappers that handle a few options themselves and pass everything else to some other
+/// program.
+///
+/// Unlike a [`strict`](crate::parsers::ParsePositional::strict) positional tail this doesn't
+/// require user to separate forwarded items with `--`, but for the same reason items that look
+/// like flags are not captured unless they go after `--`, otherwise `bpaf` reports them as
+/// unexpected. To capture everything as is take a look at [`any`](crate::any).
+///
+/// Since it's a positional parser it must go last in the structure.
+///
+/// # Usage
+/// ```rust
+/// # use bpaf::*;
+/// use bpaf::batteries::forward_args;
+/// use std::ffi::OsString;
+///
+/// fn options() -> OptionParser<(bool, Vec<OsString>)> {
+///     let dry_run = long("dry-run").switch();
+///     let args = forward_args();
+///     construct!(dry_run, args).to_options()
+/// }
+/// # let r = options().run_inner(&["--dry-run", "build", "--", "--release"]).unwrap();
+/// # assert_eq!(r, (true, vec![OsString::from("build"), OsString::from("--release")]));
+/// # let r = options().run_inner(&["--help"]).unwrap_err().unwrap_stdout();
+/// # assert_eq!(r, "Usage: [--dry-run]\n\nAvailable options:\n        --dry-run\n    -h, --help     Prints help information\n");
+/// ```
+#[must_use]
+pub fn forward_args() -> impl Parser<Vec<OsString>> {
+    positional::<OsString>("ARGS").many().hide()
+}
+
+/// Parse two items where the second one defaults to a value derived from the first one
+///
+/// A common pattern for layered defaults: `--output` defaults to `--input` with a changed
+/// extension, a cache directory defaults to a subdirectory of the working directory, etc.
+/// `source` is parsed as usual, `target` is made [`optional`](Parser::optional) and if it is
+/// absent `fallback` computes the value from the `source`. Result is a tuple with both values.
+///
+/// You can read the code of this function as this approximate sequence of statements:
+/// 1. Parse `source` and optional `target` as a tuple
+/// 2. If `target` is missing - compute it from `source`
+///
+/// ```rust
+/// # use bpaf::*;
+/// use bpaf::batteries::fallback_to;
+/// use std::path::PathBuf;
+///
+/// fn options() -> OptionParser<(PathBuf, PathBuf)> {
+///     let input = long("input").argument::<PathBuf>("FILE");
+///     let output = long("output")
+///         .help("Defaults to input with .out extension")
+///         .argument::<PathBuf>("FILE");
+///     fallback_to(input, output, |i| i.with_extension("out")).to_options()
+/// }
+/// # let r = options().run_inner(&["--input", "a.txt"]).unwrap();
+/// # assert_eq!(r, (PathBuf::from("a.txt"), PathBuf::from("a.out")));
+/// # let r = options().run_inner(&["--input", "a.txt", "--output", "b"]).unwrap();
+/// # assert_eq!(r, (PathBuf::from("a.txt"), PathBuf::from("b")));
+/// ```
+#[must_use]
+pub fn fallback_to<A, B, S, T, F>(source: S, target: T, fallback: F) -> impl Parser<(A, B)>
+where
+    S: Parser<A>,
+    T: Parser<B>,
+    F: Fn(&A) -> B + 'static,
+{
+    let target = target.optional();
+    construct!(source, target).map(move |(a, b)| {
+        let b = b.unwrap_or_else(|| fallback(&a));
+        (a, b)
+    })
+}
+
+/// Argument that takes either a single value `N` or an inclusive range `N-M`
+///
+/// Useful for page or line selection: `--page 1-5 --page 8`. Each occurrence produces a
+/// [`RangeInclusive`], a single value `N` becomes `N..=N`. Range with the start greater than
+/// the end is rejected with an error. Values that start with `-` are allowed: `-3-5` is a
+/// range from `-3` to `5`, but they need to be passed as `--page=-3-5` like any other
+/// argument value that looks like a flag.
+///
+/// To accept several occurrences use [`many`](Parser::many), to expand ranges into individual
+/// values flatten them: for numeric types `RangeInclusive` is an iterator.
+///
+/// You can read the code of this function as this approximate sequence of statements:
+/// 1. Parse an argument as a string
+/// 2. Split it on the first `-` that is not a sign of the first value
+/// 3. Parse both halves and check that the range is not empty
+///
+/// ```rust
+/// # use bpaf::*;
+/// use bpaf::batteries::range_argument;
+///
+/// fn pages() -> impl Parser<Vec<u32>> {
+///     range_argument::<u32>(long("page").help("Pages to print"), "PAGES")
+///         .many()
+///         .map(|ranges| ranges.into_iter().flatten().collect())
+/// }
+/// # let parser = pages().to_options();
+/// # let r = parser.run_inner(&["--page", "1-3", "--page", "8"]).unwrap();
+/// # assert_eq!(r, [1, 2, 3, 8]);
+/// ```
+#[must_use]
+pub fn range_argument<T>(named: NamedArg, metavar: &'static str) -> impl Parser<RangeInclusive<T>>
+where
+    T: FromStr + PartialOrd + 'static,
+    <T as FromStr>::Err: std::fmt::Display,
+{
+    named.argument::<String>(metavar).parse(|input| {
+        // skip the first character so a sign of the first value is not treated as a separator
+        let split = input
+            .char_indices()
+            .skip(1)
+            .find(|(_, c)| *c == '-')
+            .map(|(ix, _)| ix);
+        let (start, end) = match split {
+            Some(ix) => (&input[..ix], &input[ix + 1..]),
+            None => (input.as_str(), input.as_str()),
+        };
+        let start = T::from_str(start).map_err(|e| e.to_string())?;
+        let end = T::from_str(end).map_err(|e| e.to_string())?;
+        if start > end {
+            return Err(format!("invalid range `{}`: start must be <= end", input));
+        }
+        Ok(start..=end)
+    })
+}
+
+/// Option that can be passed either as `--key value` or as a positional `key=value`
+///
+/// Tools like `dd` take their options as `key=value` words, but it is often convenient to
+/// accept the usual `--key value` form for the same logical option as well. Both spellings
+/// produce the same value and are parsed with [`FromStr`].
+///
+/// Both alternatives consume items from anywhere on the command line and only one of them can
+/// be used in a single invocation: `--bs 512 bs=1024` is rejected as a conflict rather than
+/// picking one of the values. When neither form is present the parser fails as a usual
+/// required argument, use [`fallback`](Parser::fallback) or [`optional`](Parser::optional) to
+/// change that.
+///
+/// You can read the code of this function as this approximate sequence of statements:
+/// 1. Parse a named argument `--key VALUE`
+/// 2. Or parse any word that starts with `key=` and take the rest of it
+/// 3. Parse the resulting string into `T`
+///
+/// ```rust
+/// # use bpaf::*;
+/// use bpaf::batteries::long_or_key_value;
+///
+/// fn block_size() -> impl Parser<usize> {
+///     long_or_key_value("bs", "SIZE", "read/write SIZE blocks at once").fallback(512)
+/// }
+/// # let parser = block_size().to_options();
+/// # assert_eq!(parser.run_inner(&["--bs", "16"]).unwrap(), 16);
+/// # assert_eq!(parser.run_inner(&["bs=32"]).unwrap(), 32);
+/// # assert_eq!(parser.run_inner(&[]).unwrap(), 512);
+/// ```
+#[must_use]
+pub fn long_or_key_value<T>(
+    name: &'static str,
+    metavar: &'static str,
+    help: &'static str,
+) -> impl Parser<T>
+where
+    T: FromStr + 'static,
+    <T as FromStr>::Err: std::fmt::Display,
+{
+    let named = long(name).help(help).argument::<T>(metavar);
+    let key_value = any::<String, _, _>(metavar, move |s| {
+        let value = s.strip_prefix(name)?.strip_prefix('=')?;
+        Some(value.to_owned())
+    })
+    .metavar(
+        &[
+            (name, Style::Literal),
+            ("=", Style::Text),
+            (metavar, Style::Metavar),
+        ][..],
+    )
+    .help(help)
+    .anywhere()
+    .parse(|s| T::from_str(&s));
+    construct!([named, key_value])
+}
+
+/// Argument that accepts one of the listed values and completes them with descriptions
+///
+/// Takes pairs of values and their descriptions. Any other value is rejected with an error
+/// that lists the accepted ones, `--help` lists them as `[possible values: json, text]` under
+/// the argument's help. With `autocomplete` feature enabled shell completion offers
+/// the values that start with what user typed so far, descriptions show up next to them in
+/// shells that support it, such as `zsh` or `fish`.
+///
+/// You can read the code of this function as this approximate sequence of statements:
+/// 1. Parse an argument as a string
+/// 2. Complete it with values that start with the current input and their descriptions
+/// 3. Check that the value is one of the listed ones
+/// 4. Add the list of possible values to the help message
+///
+/// ```rust
+/// # use bpaf::*;
+/// use bpaf::batteries::complete_values;
+///
+/// fn format() -> impl Parser<String> {
+///     let values = [
+///         ("json", "Machine readable output"),
+///         ("text", "Human readable output"),
+///     ];
+///     complete_values(long("format").help("Output format"), "FORMAT", values)
+/// }
+/// # let parser = format().to_options();
+/// # assert_eq!(parser.run_inner(&["--format", "json"]).unwrap(), "json");
+/// # assert!(parser.run_inner(&["--format", "yaml"]).is_err());
+/// ```
+#[must_use]
+pub fn complete_values<const N: usize>(
+    named: NamedArg,
+    metavar: &'static str,
+    values: [(&'static str, &'static str); N],
+) -> impl Parser<String> {
+    let names = values.iter().map(|(value, _)| *value).collect::<Vec<_>>();
+    let arg = named.argument::<String>(metavar).possible_values(&names);
+    #[cfg(feature = "autocomplete")]
+    let arg = arg.complete(move |input: &String| {
+        values
+            .iter()
+            .filter(|(value, _)| value.starts_with(input.as_str()))
+            .map(|(value, descr)| (*value, Some(*descr)))
+            .collect::<Vec<_>>()
+    });
+    arg.parse(move |input| {
+        if values.iter().any(|(value, _)| *value == input) {
+            Ok(input)
+        } else {
+            let expected = values
+                .iter()
+                .map(|(value, _)| format!("`{}`", value))
+                .collect::<Vec<_>>()
+                .join(", ");
+            Err(format!(
+                "`{}` is not a valid value, expected one of {}",
+                input, expected
+            ))
+        }
+    })
+}
+
+/// Argument with binary data encoded as a hex string
+///
+/// Accepts an even number of hex digits `0-9`, `a-f` and `A-F`, in any case, optionally prefixed
+/// with `0x` or `0X`. Separators such as spaces or `:` are not allowed. Empty value decodes into
+/// an empty vector. Invalid values are rejected with an error that points at the offending
+/// character.
+///
+/// You can read the code of this function as this approximate sequence of statements:
+/// 1. Parse an argument as a string
+/// 2. Strip the optional `0x` prefix
+/// 3. Decode every pair of hex digits into a byte
+///
+/// ```rust
+/// # use bpaf::*;
+/// use bpaf::batteries::hex_bytes;
+///
+/// fn key() -> impl Parser<Vec<u8>> {
+///     hex_bytes(long("key").help("Encryption key"), "HEX")
+/// }
+/// # let parser = key().to_options();
+/// # assert_eq!(parser.run_inner(&["--key", "0xC0ffee"]).unwrap(), [0xc0, 0xff, 0xee]);
+/// # assert!(parser.run_inner(&["--key", "c0f"]).is_err());
+/// ```
+#[cfg(feature = "encoding")]
+#[cfg_attr(docsrs, doc(cfg(feature = "encoding")))]
+#[must_use]
+pub fn hex_bytes(named: NamedArg, metavar: &'static str) -> impl Parser<Vec<u8>> {
+    named.argument::<String>(metavar).parse(|input| {
+        let digits = input
+            .strip_prefix("0x")
+            .or_else(|| input.strip_prefix("0X"))
+            .unwrap_or(&input);
+        let offset = input.len() - digits.len();
+        let mut res = Vec::with_capacity(digits.len() / 2);
+        let mut acc = None;
+        for (ix, c) in digits.char_indices() {
+            let digit = match c.to_digit(16) {
+                Some(d) => d as u8,
+                None => {
+                    return Err(format!(
+                        "invalid hex digit `{}` at position {}",
+                        c,
+                        ix + offset
+                    ))
+                }
+            };
+            acc = match acc {
+                None => Some(digit),
+                Some(hi) => {
+                    res.push(hi << 4 | digit);
+                    None
+                }
+            };
+        }
+        if acc.is_some() {
+            return Err(format!("`{}` has an odd number of hex digits", input));
+        }
+        Ok(res)
+    })
+}
+
+/// Argument with binary data encoded as base64
+///
+/// Accepts the standard base64 alphabet from RFC 4648: `A-Z`, `a-z`, `0-9`, `+` and `/`.
+/// URL safe alphabet with `-` and `_` is not accepted, neither are whitespace and line breaks.
+/// Padding with `=` is optional, but if present it must bring the length of the value to a
+/// multiple of 4. Invalid values are rejected with an error that points at the offending
+/// character.
+///
+/// You can read the code of this function as this approximate sequence of statements:
+/// 1. Parse an argument as a string
+/// 2. Strip and check the `=` padding
+/// 3. Decode every character into 6 bits and collect them into bytes
+///
+/// ```rust
+/// # use bpaf::*;
+/// use bpaf::batteries::base64_bytes;
+///
+/// fn payload() -> impl Parser<Vec<u8>> {
+///     base64_bytes(long("payload").help("Data to send"), "BASE64")
+/// }
+/// # let parser = payload().to_options();
+/// # assert_eq!(parser.run_inner(&["--payload", "aGk="]).unwrap(), b"hi");
+/// # assert_eq!(parser.run_inner(&["--payload", "aGk"]).unwrap(), b"hi");
+/// # assert!(parser.run_inner(&["--payload", "aG%k"]).is_err());
+/// ```
+#[cfg(feature = "encoding")]
+#[cfg_attr(docsrs, doc(cfg(feature = "encoding")))]
+#[must_use]
+pub fn base64_bytes(named: NamedArg, metavar: &'static str) -> impl Parser<Vec<u8>> {
+    named.argument::<String>(metavar).parse(|input| {
+        let data = input.trim_end_matches('=');
+        let padding = input.len() - data.len();
+        if padding > 2 || (padding > 0 && input.len() % 4 != 0) {
+            return Err(format!("`{}` has invalid base64 padding", input));
+        }
+        if data.len() % 4 == 1 {
+            return Err(format!("`{}` has invalid base64 length", input));
+        }
+
+        let mut res = Vec::with_capacity(data.len() * 3 / 4);
+        let mut acc = 0u32;
+        let mut bits = 0;
+        for (ix, c) in data.char_indices() {
+            let value = match c {
+                'A'..='Z' => c as u32 - 'A' as u32,
+                'a'..='z' => c as u32 - 'a' as u32 + 26,
+                '0'..='9' => c as u32 - '0' as u32 + 52,
+                '+' => 62,
+                '/' => 63,
+                _ => {
+                    return Err(format!(
+                        "invalid base64 character `{}` at position {}",
+                        c, ix
+                    ))
+                }
+            };
+            acc = acc << 6 | value;
+            bits += 6;
+            if bits >= 8 {
+                bits -= 8;
+                res.push((acc >> bits) as u8);
+                acc &= (1 << bits) - 1;
+            }
+        }
+        Ok(res)
+    })
+}
+
 /// Strip a command name if present at the front when used as a `cargo` command
 ///
 /// When implementing a cargo subcommand parser needs to be able to skip the first argument which
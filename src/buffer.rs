@@ -9,12 +9,15 @@ use crate::{
 };
 
 mod console;
+#[cfg(feature = "docgen")]
+mod dot;
 mod html;
 #[cfg(feature = "docgen")]
 mod manpage;
 mod splitter;
 
 pub(crate) use self::console::Color;
+pub use self::console::ColorChoice;
 use self::console::MAX_WIDTH;
 
 #[cfg(feature = "docgen")]
@@ -148,7 +151,7 @@ impl Doc {
     pub(crate) fn write_item(&mut self, item: &Item) {
         match item {
             Item::Positional { metavar, help: _ } => {
-                self.metavar(*metavar);
+                self.metavar(metavar);
             }
             Item::Command {
                 name: _,
@@ -162,19 +165,23 @@ impl Doc {
             Item::Flag {
                 name,
                 shorts: _,
+                longs: _,
                 env: _,
                 help: _,
             } => self.write_shortlong(name),
             Item::Argument {
                 name,
                 shorts: _,
+                longs: _,
                 metavar,
                 env: _,
                 help: _,
+                sep,
             } => {
                 self.write_shortlong(name);
-                self.write_char('=', Style::Text);
-                self.metavar(*metavar);
+                let long = !matches!(name, ShortLong::Short(_));
+                self.write_str(sep.as_str(long), Style::Text);
+                self.metavar(metavar);
             }
             Item::Any {
                 metavar,
@@ -224,7 +231,7 @@ impl Doc {
                 Meta::Adjacent(m) | Meta::Subsection(m, _) | Meta::Suffix(m, _) => {
                     go(m, f);
                 }
-                Meta::Skip => {} // => f.write_str("no parameters expected", Style::Text),
+                Meta::Skip | Meta::Env(_) => {} // => f.write_str("no parameters expected", Style::Text),
                 Meta::CustomUsage(_, u) => {
                     f.doc(u);
                 }
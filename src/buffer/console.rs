@@ -52,8 +52,47 @@ pub(crate) enum Color {
     Bright,
 }
 
+/// When to use colors for the messages printed by [`OptionParser::run`](crate::OptionParser::run)
+///
+/// Colors are only available with one of the `color` cargo features enabled, without them
+/// output is always monochrome. See [`color_help`](crate::OptionParser::color_help) and
+/// [`color_error`](crate::OptionParser::color_error).
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum ColorChoice {
+    /// Use colors if the output stream supports them and `NO_COLOR` is not set
+    Auto,
+    /// Always use colors, regardless of the output stream and `NO_COLOR`
+    Always,
+    /// Never use colors
+    Never,
+}
+
+impl Default for ColorChoice {
+    fn default() -> Self {
+        ColorChoice::Auto
+    }
+}
+
 impl Default for Color {
     fn default() -> Self {
+        #![allow(clippy::let_and_return)]
+        #![allow(unused_mut)]
+        let mut res = Color::from_features();
+
+        #[cfg(feature = "color")]
+        {
+            use supports_color::{on, Stream};
+            if !(on(Stream::Stdout).is_some() && on(Stream::Stderr).is_some()) {
+                res = Color::Monochrome;
+            }
+        }
+        res
+    }
+}
+
+impl Color {
+    /// Color palette picked by cargo features, terminal is not checked
+    fn from_features() -> Self {
         #![allow(clippy::let_and_return)]
         #![allow(unused_mut)]
         #![allow(unused_assignments)]
@@ -77,15 +116,32 @@ impl Default for Color {
         {
             res = Color::Dull;
         }
+        res
+    }
 
-        #[cfg(feature = "color")]
-        {
-            use supports_color::{on, Stream};
-            if !(on(Stream::Stdout).is_some() && on(Stream::Stderr).is_some()) {
-                res = Color::Monochrome;
+    /// Pick colors for a message printed to `stdout` or `stderr`
+    pub(crate) fn choose(choice: ColorChoice, stderr: bool) -> Self {
+        match choice {
+            ColorChoice::Never => Color::Monochrome,
+            ColorChoice::Always => Color::from_features(),
+            ColorChoice::Auto => {
+                #[cfg(feature = "color")]
+                {
+                    use supports_color::{on, Stream};
+                    let stream = if stderr {
+                        Stream::Stderr
+                    } else {
+                        Stream::Stdout
+                    };
+                    if on(stream).is_none() {
+                        return Color::Monochrome;
+                    }
+                }
+                #[cfg(not(feature = "color"))]
+                let _ = stderr;
+                Color::from_features()
             }
         }
-        res
     }
 }
 
@@ -0,0 +1,114 @@
+use std::fmt::Write;
+
+use crate::{item::Item, Doc, Meta, OptionParser, Parser};
+
+impl<T> OptionParser<T> {
+    /// Render the structure of the parser as a Graphviz diagram in DOT format
+    ///
+    /// Output can be converted into an image with `dot -Tsvg`. Conventions used:
+    ///
+    /// - product groups (all items must be present) are ellipses labelled `and`
+    /// - sum groups (one of the items must be present) are diamonds labelled `or`
+    /// - `optional`, `many`, `adjacent`, `strict` (must follow `--`) and `env` (environment
+    ///   variables only) are ellipses with a single child each
+    /// - flags, arguments and positional items are boxes labelled the same way as in the usage line
+    /// - commands are bold boxes labelled with the command name, pointing to the command's parser
+    ///
+    /// Edges point from a group to its members in the order they are declared. Groups with a
+    /// single member are replaced with the member itself, decorations such as
+    /// [`group_help`](Parser::group_help) or [`custom_usage`](Parser::custom_usage) don't
+    /// change the structure and are omitted.
+    ///
+    /// ```rust
+    /// # use bpaf::*;
+    /// let verbose = short('v').switch();
+    /// let name = positional::<String>("NAME");
+    /// let parser = construct!(verbose, name).to_options();
+    /// let dot = parser.render_dot();
+    /// assert!(dot.starts_with("digraph bpaf {"));
+    /// assert!(dot.contains("label=\"-v\""));
+    /// ```
+    #[must_use]
+    pub fn render_dot(&self) -> String {
+        let mut dot = Dot::default();
+        dot.res.push_str("digraph bpaf {\n");
+        dot.res.push_str("    node [fontname=\"monospace\"];\n");
+        dot.meta(&self.inner.meta());
+        dot.res.push_str("}\n");
+        dot.res
+    }
+}
+
+#[derive(Default)]
+struct Dot {
+    res: String,
+    next: usize,
+}
+
+impl Dot {
+    fn node(&mut self, label: &str, attrs: &str) -> usize {
+        let id = self.next;
+        self.next += 1;
+        let label = label.replace('\\', "\\\\").replace('"', "\\\"");
+        let _ = writeln!(self.res, "    n{} [label=\"{}\", {}];", id, label, attrs);
+        id
+    }
+
+    fn edge(&mut self, from: usize, to: usize) {
+        let _ = writeln!(self.res, "    n{} -> n{};", from, to);
+    }
+
+    fn meta(&mut self, meta: &Meta) -> Option<usize> {
+        match meta {
+            Meta::And(xs) => self.group("and", "shape=ellipse", xs),
+            Meta::Or(xs) => self.group("or", "shape=diamond", xs),
+            Meta::Optional(m) => self.wrap("optional", m),
+            Meta::Many(m) => self.wrap("many", m),
+            Meta::Adjacent(m) => self.wrap("adjacent", m),
+            Meta::Strict(m) => self.wrap("strict", m),
+            Meta::Env(m) => self.wrap("env", m),
+            Meta::Required(m)
+            | Meta::Subsection(m, _)
+            | Meta::Suffix(m, _)
+            | Meta::CustomUsage(m, _) => self.meta(m),
+            Meta::Item(item) => Some(self.item(item)),
+            Meta::Skip => None,
+        }
+    }
+
+    fn group(&mut self, label: &str, attrs: &str, xs: &[Meta]) -> Option<usize> {
+        let children = xs.iter().filter_map(|x| self.meta(x)).collect::<Vec<_>>();
+        match children.as_slice() {
+            [] => None,
+            [single] => Some(*single),
+            _ => {
+                let id = self.node(label, attrs);
+                for child in children {
+                    self.edge(id, child);
+                }
+                Some(id)
+            }
+        }
+    }
+
+    fn wrap(&mut self, label: &str, inner: &Meta) -> Option<usize> {
+        let child = self.meta(inner)?;
+        let id = self.node(label, "shape=ellipse");
+        self.edge(id, child);
+        Some(id)
+    }
+
+    fn item(&mut self, item: &Item) -> usize {
+        if let Item::Command { name, meta, .. } = item {
+            let id = self.node(name, "shape=box, style=bold");
+            if let Some(child) = self.meta(meta) {
+                self.edge(id, child);
+            }
+            id
+        } else {
+            let mut doc = Doc::default();
+            doc.write_item(item);
+            self.node(&doc.monochrome(false), "shape=box")
+        }
+    }
+}
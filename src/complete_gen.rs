@@ -16,7 +16,9 @@
 
 use crate::{
     args::{Arg, State},
-    complete_shell::{render_bash, render_fish, render_simple, render_test, render_zsh},
+    complete_shell::{
+        render_bash, render_elvish, render_fish, render_simple, render_test, render_zsh,
+    },
     item::ShortLong,
     parsers::NamedArg,
     Doc, ShellComp,
@@ -127,6 +129,12 @@ impl State {
         }
     }
 
+    /// Check if the item being completed looks like a flag: `-f` or `--fla`
+    pub(crate) fn completing_named(&self) -> bool {
+        self.comp_ref().is_some()
+            && matches!(self.items.last(), Some(Arg::Short(..) | Arg::Long(..)))
+    }
+
     /// Clear collected completions if enabled
     pub(crate) fn clear_comps(&mut self) {
         if let Some(comp) = self.comp_mut() {
@@ -152,6 +160,18 @@ impl State {
         }
     }
 
+    /// Insert a bunch of items, arguments and metavariables get a fallback value in their help
+    pub(crate) fn push_with_default(&mut self, default: &str, comps: &mut Vec<Comp>) {
+        if let Some(comp) = self.comp_mut() {
+            for mut item in comps.drain(..) {
+                if !default.is_empty() {
+                    item.add_default(default);
+                }
+                comp.comps.push(item);
+            }
+        }
+    }
+
     /// Insert a bunch of items
     pub(crate) fn push_with_group(&mut self, group: &Option<String>, comps: &mut Vec<Comp>) {
         if let Some(comp) = self.comp_mut() {
@@ -303,6 +323,20 @@ impl Comp {
             extra.group = Some(group);
         }
     }
+
+    /// Show a value used when item is absent next to its help, `[default: json]`
+    fn add_default(&mut self, default: &str) {
+        let extra = match self {
+            Comp::Argument { extra, .. } | Comp::Metavariable { extra, .. } => extra,
+            Comp::Flag { .. } | Comp::Command { .. } | Comp::Value { .. } | Comp::Shell { .. } => {
+                return
+            }
+        };
+        extra.help = Some(match extra.help.take() {
+            Some(help) => format!("{} {}", help, default),
+            None => default.to_owned(),
+        });
+    }
 }
 
 #[derive(Debug)]
@@ -389,7 +423,8 @@ impl State {
             Some((Arg::Short(_, true, _os) | Arg::Long(_, true, _os), full_lit)) => {
                 (false, full_lit)
             }
-            Some((Arg::PosWord(_), _)) => (true, lit),
+            // `--` from prepend_args doesn't affect items after it
+            Some((Arg::PosWord(_), _)) if matches!(cur, Arg::PosWord(_)) => (true, lit),
             _ => (false, lit),
         };
 
@@ -408,7 +443,8 @@ impl State {
 
         Some(match comp.output_rev {
             0 => render_test(&items, &shell, full_lit),
-            1 => render_simple(&items), // <- AKA elvish
+            1 => render_simple(&items), // <- AKA elvish, older completion scripts
+            2 => render_elvish(&items, &shell),
             7 => render_zsh(&items, &shell, full_lit),
             8 => render_bash(&items, &shell, full_lit),
             9 => render_fish(&items, &shell, full_lit, self.path[0].as_str()),
@@ -435,13 +471,27 @@ fn preferred_name(name: ShortLong) -> String {
     }
 }
 
+/// Use short name if possible, long name otherwise
+fn short_name(name: ShortLong) -> String {
+    match name {
+        ShortLong::Short(s) | ShortLong::Both(s, _) => format!("-{}", s),
+        ShortLong::Long(l) => format!("--{}", l),
+    }
+}
+
 // check if argument can possibly match the argument passed in and returns a preferrable replacement
 fn arg_matches(arg: &str, name: ShortLong) -> Option<String> {
-    // "" and "-" match any flag
-    if arg.is_empty() || arg == "-" {
+    // "" matches any flag
+    if arg.is_empty() {
         return Some(preferred_name(name));
     }
 
+    // "-" matches any flag too, but user is asking for short names,
+    // "--" is handled below and matches long names only
+    if arg == "-" {
+        return Some(short_name(name));
+    }
+
     let mut can_match = false;
 
     // separately check for short and long names, fancy strip prefix things is here to avoid
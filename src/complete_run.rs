@@ -1,15 +1,55 @@
 use crate::complete_gen::Complete;
-use std::ffi::OsStr;
+use std::{cell::Cell, ffi::OsStr};
+
+thread_local! {
+    static COMPLETING: Cell<bool> = Cell::new(false);
+}
+
+pub(crate) fn is_completing() -> bool {
+    COMPLETING.with(Cell::get)
+}
+
+/// Marks the current thread as running a completion request until dropped
+pub(crate) struct CompletingGuard(bool);
+
+impl CompletingGuard {
+    pub(crate) fn new(active: bool) -> Self {
+        Self(COMPLETING.with(|c| c.replace(active)))
+    }
+}
+
+impl Drop for CompletingGuard {
+    fn drop(&mut self) {
+        COMPLETING.with(|c| c.set(self.0));
+    }
+}
 
 fn dump_bash_completer(name: &str) {
     println!(
         r#"_bpaf_dynamic_completion()
 {{
-    line="$1 --bpaf-complete-rev=8 ${{COMP_WORDS[@]:1}}"
+    local words=() i
+    # bash splits `--key=value` into `--key`, `=` and `value`, glue them back together
+    for (( i=1; i<${{#COMP_WORDS[@]}}; i++ )); do
+        if [[ ${{COMP_WORDS[i]}} == "=" && ${{#words[@]}} -gt 0 && ${{words[-1]}} == -* ]]; then
+            words[-1]+="="
+            if (( i + 1 < ${{#COMP_WORDS[@]}} )); then
+                (( i++ ))
+                words[-1]+="${{COMP_WORDS[i]}}"
+            fi
+        else
+            words+=("${{COMP_WORDS[i]}}")
+        fi
+    done
+    line="$1 --bpaf-complete-rev=8 ${{words[@]}}"
     if [[ ${{COMP_WORDS[-1]}} == "" ]]; then
         line="${{line}} \"\""
     fi
     source <( eval ${{line}})
+    # bash only replaces the part after `=`, strip `--key=` from the replacements
+    if [[ $COMP_WORDBREAKS == *=* && ${{words[-1]}} == -*=* ]]; then
+        COMPREPLY=( "${{COMPREPLY[@]#"${{words[-1]%%=*}}="}}" )
+    fi
 }}
 complete -o nosort -F _bpaf_dynamic_completion {name}"#,
         name = name,
@@ -33,12 +73,10 @@ source <(eval ${{line}})
 fn dump_fish_completer(name: &str) {
     println!(
         r#"function _bpaf_dynamic_completion
-    set -l current (commandline --tokenize --current-process)
-    set -l tmpline --bpaf-complete-rev=9 $current[2..]
-    if test (commandline --current-process) != (string trim (commandline --current-process))
-        set tmpline $tmpline ""
-    end
-    eval $current[1] \"$tmpline\"
+    # tokens before the cursor followed by the token being completed, empty after a space
+    set -l current (commandline --tokenize --current-process --cut-at-cursor)
+    set -l tmpline --bpaf-complete-rev=9 $current[2..] (commandline --current-token --cut-at-cursor)
+    eval $current[1] (string escape -- $tmpline)
 end
 
 complete --no-files --command {name} --arguments '(_bpaf_dynamic_completion)'
@@ -59,15 +97,15 @@ set edit:completion:arg-completer[{name}] = {{ |@args| var args = $args[1..];
      use str;
      for line $lines {{
          var @arg = (str:split \"\\t\" $line)
-         try {{
-             edit:complex-candidate $arg[0] &display=( printf \"%-19s %s\" $arg[0] $arg[1] )
-         }} catch {{
+         if (== (count $arg) 2) {{
+             edit:complex-candidate $arg[0] &display=$arg[1]
+         }} else {{
              edit:complex-candidate $line
          }}
      }}
 }}",
         name = name,
-        rev = 1,
+        rev = 2,
     );
 }
 
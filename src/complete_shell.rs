@@ -63,6 +63,30 @@ pub enum ShellComp {
         elvish: &'static str,
     },
 
+    /// Read candidates from a file when completion is requested
+    ///
+    /// Useful for large static sets of values maintained outside of the program, such as a list
+    /// of package names: the file is read by the shell or by `bpaf` each time user asks for
+    /// completion so the list can be updated without rebuilding the app and doesn't take space
+    /// in the binary.
+    ///
+    /// File contains one candidate per line, optionally followed by a tab and a description,
+    /// here `<TAB>` stands for a single tab character:
+    ///
+    /// ```text
+    /// serde<TAB>A generic serialization/deserialization framework
+    /// syn<TAB>Parser for Rust source code
+    /// tokio
+    /// ```
+    ///
+    /// Empty lines are ignored. Descriptions are shown by `zsh`, `fish` and `elvish`, `bash` shows
+    /// only the candidates. Relative paths are resolved against the current directory of the
+    /// shell, missing file produces no candidates.
+    FromFile {
+        /// Path to a file with candidates, no newlines
+        path: &'static str,
+    },
+
     /// Don't produce anything at all from this parser - can be useful if you want to compose
     /// bpaf completion with shell completion
     Nothing,
@@ -132,6 +156,18 @@ pub(crate) fn render_zsh(
             ShellComp::Dir { mask: None } => writeln!(res, "_files -/"),
             ShellComp::Dir { mask: Some(mask) } => writeln!(res, "_files -/ -g {}", Shell(mask)),
             ShellComp::Raw { zsh, .. } => writeln!(res, "{}", zsh),
+            ShellComp::FromFile { path } => writeln!(
+                res,
+                "local -a bpaf_subst bpaf_descr
+local bpaf_cand bpaf_help
+[[ -r {path} ]] && while IFS=$'\\t' read -r bpaf_cand bpaf_help || [[ -n $bpaf_cand ]]; do
+    [[ -n $bpaf_cand ]] || continue
+    bpaf_subst+=(\"$bpaf_cand\")
+    bpaf_descr+=(\"$bpaf_cand${{bpaf_help:+  -- $bpaf_help}}\")
+done < {path}
+compadd -l -V nosort -d bpaf_descr -- \"${{bpaf_subst[@]}}\"",
+                path = Shell(path)
+            ),
             ShellComp::Nothing => Ok(()),
         }?;
     }
@@ -219,6 +255,15 @@ pub(crate) fn render_bash(
                 writeln!(res, "{} _filedir -d {}", init, Shell(&bashmask(mask)))
             }
             ShellComp::Raw { bash, .. } => writeln!(res, "{}", bash),
+            ShellComp::FromFile { path } => writeln!(
+                res,
+                "{} local bpaf_cand bpaf_help ; \
+[[ -r {path} ]] && while IFS=$'\\t' read -r bpaf_cand bpaf_help || [[ -n $bpaf_cand ]]; do \
+[[ -n $bpaf_cand && $bpaf_cand == \"$cur\"* ]] && COMPREPLY+=(\"$bpaf_cand\") ; \
+done < {path}",
+                init,
+                path = Shell(path)
+            ),
             ShellComp::Nothing => Ok(()),
         }?;
     }
@@ -292,6 +337,17 @@ pub(crate) fn render_fish(
         writeln!(res, "{}", full_lit)?;
     }
 
+    // fish takes candidates in the same format as the file uses, no need to involve the shell
+    for op in ops {
+        if let ShellComp::FromFile { path } = op {
+            if let Ok(file) = std::fs::read_to_string(path) {
+                for line in file.lines().filter(|l| !l.is_empty()) {
+                    writeln!(res, "{}", line)?;
+                }
+            }
+        }
+    }
+
     // skip things without substitutions, I think they
     // are headers and such, and fish is a bit
     for item in items.iter().rev().filter(|i| !i.subst.is_empty()) {
@@ -326,3 +382,43 @@ pub(crate) fn render_simple(items: &[ShowComp]) -> Result<String, std::fmt::Erro
     }
     Ok(res)
 }
+
+/// Elvish takes a display string for each candidate, padding is done here so the script doesn't
+/// need to parse or format anything
+pub(crate) fn render_elvish(
+    items: &[ShowComp],
+    ops: &[ShellComp],
+) -> Result<String, std::fmt::Error> {
+    use std::fmt::Write;
+    let mut res = String::new();
+
+    // same as with fish the file is read here, other shell completions are not supported
+    for op in ops {
+        if let ShellComp::FromFile { path } = op {
+            if let Ok(file) = std::fs::read_to_string(path) {
+                for line in file.lines().filter(|l| !l.is_empty()) {
+                    match line.split_once('\t') {
+                        Some((cand, descr)) => writeln!(res, "{}\t{:<19} {}", cand, cand, descr),
+                        None => writeln!(res, "{}", line),
+                    }?;
+                }
+            }
+        }
+    }
+
+    // items without substitutions are metavars and headers, elvish would insert them as empty
+    // strings
+    for item in items.iter().filter(|i| !i.subst.is_empty()) {
+        match item.extra.help.as_deref() {
+            Some(descr) => writeln!(
+                res,
+                "{}\t{:<19} {}",
+                item.subst,
+                item.subst,
+                descr.split('\n').next().unwrap_or("")
+            ),
+            None => writeln!(res, "{}", item.subst),
+        }?;
+    }
+    Ok(res)
+}
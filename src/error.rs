@@ -1,4 +1,4 @@
-use std::ops::Range;
+use std::{num::NonZeroU8, ops::Range};
 
 use crate::{
     args::{Arg, State},
@@ -31,6 +31,12 @@ pub(crate) enum Message {
     /// User asked for parser to fail explicitly
     ParseFail(&'static str),
 
+    /// User asked for parser to fail explicitly with a custom exit code
+    ParseFailWithCode(&'static str, NonZeroU8),
+
+    /// User asked for parser to fail and show the help message instead of an error
+    ShowHelp,
+
     /// pure_with failed to parse a value
     PureFailed(String),
 
@@ -53,6 +59,9 @@ pub(crate) enum Message {
     /// Parser provided by user failed to parse a value
     ParseFailed(Option<usize>, String),
 
+    /// Parser provided by user failed to parse a value, message is used as is
+    ParseFailedExact(String),
+
     /// Parser provided by user failed to validate a value
     GuardFailed(Option<usize>, &'static str),
 
@@ -90,11 +99,14 @@ impl Message {
             Message::NoEnv(_)
             | Message::ParseSome(_)
             | Message::ParseFail(_)
+            | Message::ParseFailWithCode(_, _)
+            | Message::ShowHelp
             | Message::Missing(_)
             | Message::PureFailed(_)
             | Message::NonStrictPos(_, _) => true,
             Message::StrictPos(_, _)
             | Message::ParseFailed(_, _)
+            | Message::ParseFailedExact(_)
             | Message::GuardFailed(_, _)
             | Message::Unconsumed(_)
             | Message::Ambiguity(_, _)
@@ -158,8 +170,8 @@ impl Message {
 /// shell completion mechanism relies on that. In both cases application should exit with error
 /// code of 0. `Stderr` variant indicates a genuinly parsing error which should be printed to
 /// stderr or a logging framework of your choice as an error and the app should exit with error
-/// code of 1. [`ParseFailure::exit_code`] is a helper method that performs printing and produces
-/// the exit code to use.
+/// code of 1 or a custom exit code set with [`fail_with_code`](crate::fail_with_code).
+/// [`ParseFailure::exit_code`] is a helper method that produces the exit code to use.
 ///
 /// For purposes of for unit testing for user parsers, you can consume it with
 /// [`ParseFailure::unwrap_stdout`] and [`ParseFailure::unwrap_stdout`] - both of which produce a
@@ -172,8 +184,9 @@ pub enum ParseFailure {
     /// This also goes to stdout with exit code of 0,
     /// this cannot be Doc because completion needs more control about rendering
     Completion(String),
-    /// Print this to stderr and exit with failure code
-    Stderr(Doc),
+    /// Print this to stderr and exit with failure code, `1` unless a custom one is set with
+    /// [`fail_with_code`](crate::fail_with_code)
+    Stderr(Doc, NonZeroU8),
 }
 
 impl ParseFailure {
@@ -186,7 +199,7 @@ impl ParseFailure {
     #[track_caller]
     pub fn unwrap_stderr(self) -> String {
         match self {
-            Self::Stderr(err) => err.monochrome(true),
+            Self::Stderr(err, _) => err.monochrome(true),
             Self::Completion(..) | Self::Stdout(..) => panic!("not an stderr: {:?}", self),
         }
     }
@@ -207,11 +220,14 @@ impl ParseFailure {
     }
 
     /// Returns the exit code for the failure
+    ///
+    /// `0` for help, version and completion output, `1` for parsing errors unless
+    /// failure comes from [`fail_with_code`](crate::fail_with_code) with a custom code.
     #[allow(clippy::must_use_candidate)]
     pub fn exit_code(self) -> i32 {
         match self {
             Self::Stdout(..) | Self::Completion(..) => 0,
-            Self::Stderr(..) => 1,
+            Self::Stderr(_, code) => i32::from(code.get()),
         }
     }
 
@@ -224,14 +240,19 @@ impl ParseFailure {
     /// Prints a message to `stdout` or `stderr` appropriate to the failure.
     pub fn print_message(&self, max_width: usize) {
         let color = Color::default();
+        self.print_colored(max_width, color, color);
+    }
+
+    /// Prints a message using separate colors for `stdout` and `stderr`
+    pub(crate) fn print_colored(&self, max_width: usize, stdout: Color, color: Color) {
         match self {
             ParseFailure::Stdout(msg, full) => {
-                println!("{}", msg.render_console(*full, color, max_width));
+                println!("{}", msg.render_console(*full, stdout, max_width));
             }
             ParseFailure::Completion(s) => {
                 print!("{}", s);
             }
-            ParseFailure::Stderr(msg) => {
+            ParseFailure::Stderr(msg, _) => {
                 #[allow(unused_mut)]
                 let mut error;
                 #[cfg(not(feature = "color"))]
@@ -276,6 +297,18 @@ fn only_once(args: &State, cur: usize) -> Option<usize> {
     Some(cur - offset? - 1)
 }
 
+/// Same as [`only_once`] but also recognizes a different name of the same item:
+/// `-a 1 --alpha 2` or `--alpha 1 --alfa 2` where `--alfa` is a hidden alias
+fn only_once_alias(args: &State, cur: usize, meta: &Meta) -> Option<usize> {
+    let (shorts, longs) = match meta.find_named(args.items.get(cur)?)? {
+        Item::Flag { shorts, longs, .. } | Item::Argument { shorts, longs, .. } => (shorts, longs),
+        Item::Any { .. } | Item::Positional { .. } | Item::Command { .. } => return None,
+    };
+    args.items[..cur].iter().rposition(|a| {
+        shorts.iter().any(|s| a.match_short(*s)) || longs.iter().any(|l| a.match_long(l))
+    })
+}
+
 impl Message {
     #[allow(clippy::too_many_lines)] // it's a huge match with lots of simple cases
     pub(crate) fn render(mut self, args: &State, meta: &Meta) -> ParseFailure {
@@ -286,6 +319,8 @@ impl Message {
                     self = conflict;
                 } else if let Some(prev_ix) = only_once(args, ix) {
                     self = Message::OnlyOnce(prev_ix, ix);
+                } else if let Some(prev_ix) = only_once_alias(args, ix, meta) {
+                    self = Message::OnlyOnce(prev_ix, ix);
                 } else if let Some((ix, suggestion)) = crate::meta_youmean::suggest(args, meta) {
                     self = Message::Suggestion(ix, suggestion);
                 }
@@ -296,6 +331,11 @@ impl Message {
             _ => {}
         }
 
+        let code = match self {
+            Message::ParseFailWithCode(_, code) => code,
+            _ => error_code(),
+        };
+
         let mut doc = Doc::default();
         match self {
             // already rendered
@@ -328,7 +368,7 @@ impl Message {
             Message::StrictPos(_ix, metavar) => {
                 doc.text("expected ");
                 doc.token(Token::BlockStart(Block::TermRef));
-                doc.metavar(metavar);
+                doc.metavar(&metavar);
                 doc.token(Token::BlockEnd(Block::TermRef));
                 doc.text(" to be on the right side of ");
                 doc.token(Token::BlockStart(Block::TermRef));
@@ -340,7 +380,7 @@ impl Message {
             Message::NonStrictPos(_ix, metavar) => {
                 doc.text("expected ");
                 doc.token(Token::BlockStart(Block::TermRef));
-                doc.metavar(metavar);
+                doc.metavar(&metavar);
                 doc.token(Token::BlockEnd(Block::TermRef));
                 doc.text(" to be on the left side of ");
                 doc.token(Token::BlockStart(Block::TermRef));
@@ -349,10 +389,16 @@ impl Message {
             }
 
             // Error: <message from some or fail>
-            Message::ParseSome(s) | Message::ParseFail(s) => {
+            Message::ParseSome(s) | Message::ParseFail(s) | Message::ParseFailWithCode(s, _) => {
                 doc.text(s);
             }
 
+            // OptionParser replaces this one with the help message, it's
+            // only rendered if parser runs outside of OptionParser
+            Message::ShowHelp => {
+                doc.text("help message was requested");
+            }
+
             // Error: couldn't parse FIELD: <FromStr message>
             Message::ParseFailed(mix, s) => {
                 doc.text("couldn't parse");
@@ -366,6 +412,11 @@ impl Message {
                 doc.text(&s);
             }
 
+            // Error: <message from parse_exact>
+            Message::ParseFailedExact(s) => {
+                doc.text(&s);
+            }
+
             // Error: ( FIELD:  | check failed: ) <message from guard>
             Message::GuardFailed(mix, s) => {
                 if let Some(field) = textual_part(args, mix) {
@@ -391,7 +442,7 @@ impl Message {
                     doc.token(Token::BlockEnd(Block::TermRef));
                     doc.text(" requires an argument ");
                     doc.token(Token::BlockStart(Block::TermRef));
-                    doc.metavar(mv);
+                    doc.metavar(&mv);
                     doc.token(Token::BlockEnd(Block::TermRef));
                     doc.text(", got a flag ");
                     doc.token(Token::BlockStart(Block::TermRef));
@@ -413,7 +464,7 @@ impl Message {
                     doc.token(Token::BlockEnd(Block::TermRef));
                     doc.text(" requires an argument ");
                     doc.token(Token::BlockStart(Block::TermRef));
-                    doc.metavar(mv);
+                    doc.metavar(&mv);
                     doc.token(Token::BlockEnd(Block::TermRef));
                 }
             },
@@ -613,10 +664,15 @@ impl Message {
             }
         };
 
-        ParseFailure::Stderr(doc)
+        ParseFailure::Stderr(doc, code)
     }
 }
 
+/// Exit code for parsing errors without a custom code
+pub(crate) fn error_code() -> NonZeroU8 {
+    NonZeroU8::new(1).expect("1 is not 0")
+}
+
 /// go over all the missing items, pick the left most scope
 pub(crate) fn summarize_missing(items: &[MissingItem], inner: &Meta, args: &State) -> Message {
     // missing items can belong to different scopes, pick the best scope to work with
@@ -631,16 +687,23 @@ pub(crate) fn summarize_missing(items: &[MissingItem], inner: &Meta, args: &Stat
     let mut best_scope = best_item.scope.clone();
 
     let mut saw_command = false;
+    let mut saw_pos = Vec::new();
     let expected = items
         .iter()
         .filter_map(|i| {
             let cmd = matches!(i.item, Item::Command { .. });
-            if i.scope == best_scope && !(saw_command && cmd) {
-                saw_command |= cmd;
-                Some(i.item.clone())
-            } else {
-                None
+            if i.scope != best_scope || (saw_command && cmd) {
+                return None;
+            }
+            // several interpretations of the same positional item are reported once
+            if let Item::Positional { metavar, .. } = &i.item {
+                if saw_pos.contains(&metavar.0) {
+                    return None;
+                }
+                saw_pos.push(metavar.0.clone());
             }
+            saw_command |= cmd;
+            Some(i.item.clone())
         })
         .collect::<Vec<_>>();
 
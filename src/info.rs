@@ -1,12 +1,18 @@
 //! Help message generation and rendering
 
 use crate::{
-    args::{Args, State},
+    args::{Arg, Args, State},
+    buffer::{Block, Color, Token},
     error::Message,
+    item::Item,
+    meta::Header,
     meta_help::render_help,
     parsers::NamedArg,
-    short, Doc, Error, Meta, ParseFailure, Parser,
+    short,
+    structs::GlobalItem,
+    ColorChoice, Doc, Error, Meta, ParseFailure, Parser,
 };
+use std::ffi::OsString;
 
 /// Information about the parser
 ///
@@ -16,6 +22,8 @@ use crate::{
 pub struct Info {
     /// version field, see [`version`][Info::version]
     pub version: Option<Doc>,
+    /// enabled features to list after the version, see [`version_features`][OptionParser::version_features]
+    pub version_features: Option<Vec<String>>,
     /// Custom description field, see [`descr`][Info::descr]
     pub descr: Option<Doc>,
     /// Custom header field, see [`header`][Info::header]
@@ -24,26 +32,46 @@ pub struct Info {
     pub footer: Option<Doc>,
     /// Custom usage field, see [`usage`][Info::usage]
     pub usage: Option<Doc>,
+    /// Usage examples, see [`example`][OptionParser::example]
+    pub examples: Vec<(String, Doc)>,
     pub help_arg: NamedArg,
     pub version_arg: NamedArg,
     pub help_if_no_args: bool,
     pub max_width: usize,
+    pub page_help: bool,
+    pub color_help: ColorChoice,
+    pub color_error: ColorChoice,
+    pub prepend_args: Vec<OsString>,
+    pub default_command: Option<&'static str>,
+    pub name: Option<&'static str>,
+    pub help_command: bool,
+    pub split_required: bool,
 }
 
 impl Default for Info {
     fn default() -> Self {
         Self {
             version: None,
+            version_features: None,
             descr: None,
             header: None,
             footer: None,
             usage: None,
+            examples: Vec::new(),
             help_arg: short('h').long("help").help("Prints help information"),
             version_arg: short('V')
                 .long("version")
                 .help("Prints version information"),
             help_if_no_args: false,
             max_width: 100,
+            page_help: false,
+            color_help: ColorChoice::Auto,
+            color_error: ColorChoice::Auto,
+            prepend_args: Vec::new(),
+            default_command: None,
+            name: None,
+            help_command: false,
+            split_required: false,
         }
     }
 }
@@ -90,7 +118,21 @@ impl<T> OptionParser<T> {
         match self.run_inner(Args::current_args()) {
             Ok(t) => t,
             Err(err) => {
-                err.print_message(self.info.max_width);
+                if let ParseFailure::Stdout(msg, full) = &err {
+                    let pager = std::env::var("PAGER").ok();
+                    if self.info.page_help
+                        && !is_version(msg)
+                        && stdout_is_terminal()
+                        && page_message(msg, *full, self.info.max_width, pager.as_deref())
+                    {
+                        std::process::exit(0)
+                    }
+                }
+                err.print_colored(
+                    self.info.max_width,
+                    Color::choose(self.info.color_help, false),
+                    Color::choose(self.info.color_error, true),
+                );
                 std::process::exit(err.exit_code())
             }
         }
@@ -127,7 +169,7 @@ impl<T> OptionParser<T> {
     ///             print!("{}", msg);
     ///             None
     ///         }
-    ///         Err(ParseFailure::Stderr(buf)) => {
+    ///         Err(ParseFailure::Stderr(buf, _)) => {
     ///             eprintln!("{}", buf.monochrome(true));
     ///             None
     ///         }
@@ -184,6 +226,28 @@ impl<T> OptionParser<T> {
     /// See also [`Args`] and it's `From` impls to produce input and
     /// [`ParseFailure::unwrap_stderr`] / [`ParseFailure::unwrap_stdout`] for processing results.
     ///
+    /// # Testing help output
+    ///
+    /// Unlike [`run`](OptionParser::run), `run_inner` doesn't look at the terminal or the
+    /// environment: [`ParseFailure::unwrap_stdout`] and [`ParseFailure::unwrap_stderr`] always
+    /// render the message without colors and wrap it at 100 characters, ignoring
+    /// [`max_width`](OptionParser::max_width), [`color_help`](OptionParser::color_help) and
+    /// [`color_error`](OptionParser::color_error), so help snapshots are stable across
+    /// machines. To check how a message wraps at a different width, take the [`Doc`] out of
+    /// [`ParseFailure::Stdout`] and format it with an explicit width:
+    ///
+    /// ```rust
+    /// # use bpaf::*;
+    /// let parser = short('v')
+    ///     .help("Print more details about what is being done right now")
+    ///     .switch()
+    ///     .to_options();
+    /// if let Err(ParseFailure::Stdout(doc, _)) = parser.run_inner(&["--help"]) {
+    ///     let narrow = format!("{:40}", doc);
+    ///     assert!(narrow.lines().all(|line| line.len() <= 40));
+    /// }
+    /// ```
+    ///
     /// # Errors
     ///
     /// If parser can't produce desired result `run_inner` returns [`ParseFailure`]
@@ -206,10 +270,20 @@ impl<T> OptionParser<T> {
             .collect_shorts(&mut short_flags, &mut short_args);
         short_flags.extend(&self.info.help_arg.short);
         short_flags.extend(&self.info.version_arg.short);
-        let args = args.into();
+        let mut args = args.into();
+        args.prepend(&self.info.prepend_args);
         let mut err = None;
         let mut state = State::construct(args, &short_flags, &short_args, &mut err);
 
+        // completion scripts need to invoke the actual executable, name is only for the user
+        #[cfg(feature = "autocomplete")]
+        let rename = state.comp_ref().is_none();
+        #[cfg(not(feature = "autocomplete"))]
+        let rename = true;
+        if let (Some(name), true) = (self.info.name, rename) {
+            state.set_name(name);
+        }
+
         // this only handles disambiguation failure in construct
         if let Some(msg) = err {
             #[cfg(feature = "autocomplete")]
@@ -223,9 +297,124 @@ impl<T> OptionParser<T> {
             }
         }
 
+        #[cfg(feature = "autocomplete")]
+        let _completing = crate::complete_run::CompletingGuard::new(state.comp_ref().is_some());
+
         self.run_subparser(&mut state)
     }
 
+    /// Name of a command to run if command line contains none of the commands
+    ///
+    /// Default command doesn't apply if user asks for `--help` or `--version` or if shell
+    /// completion is active so they keep working for the parser itself.
+    fn pick_default_command(&self, args: &State) -> Option<&'static str> {
+        let name = self.info.default_command?;
+
+        #[cfg(feature = "autocomplete")]
+        if args.comp_ref().is_some() {
+            return None;
+        }
+
+        if self.info.eval(&mut args.clone()).is_ok() {
+            return None;
+        }
+
+        // command can only be the first positional word, skip over named items
+        // along with separate values for arguments
+        let meta = self.inner.meta();
+        let mut commands = Vec::new();
+        meta.collect_commands(&mut commands);
+        let mut items = args.items_iter();
+        let mut present = false;
+        while let Some((_, arg)) = items.next() {
+            match arg {
+                Arg::Short(_, false, _) | Arg::Long(_, false, _) => {
+                    if let Some(Item::Argument { .. }) = meta.find_named(arg) {
+                        items.next();
+                    }
+                }
+                Arg::Short(..) | Arg::Long(..) | Arg::ArgWord(_) => {}
+                Arg::Word(w) => {
+                    present = w
+                        .to_str()
+                        .map_or(false, |w| commands.iter().any(|c| c == w));
+                    break;
+                }
+                Arg::PosWord(_) => break,
+            }
+        }
+        if present {
+            None
+        } else {
+            Some(name)
+        }
+    }
+
+    /// Render help for `help build deploy` if built-in `help` command is enabled
+    fn help_for_command_path(&self, args: &State) -> Option<ParseFailure> {
+        if !self.info.help_command {
+            return None;
+        }
+
+        #[cfg(feature = "autocomplete")]
+        if args.comp_ref().is_some() {
+            return None;
+        }
+
+        let mut items = args.items_iter();
+        match items.next() {
+            Some((_, Arg::Word(w))) if w == "help" => {}
+            _ => return None,
+        }
+
+        let mut path = args.path.clone();
+        let mut meta = self.inner.meta();
+        let mut info = self.info.clone();
+        for (ix, arg) in items {
+            let command = match arg {
+                Arg::Word(w) => w.to_str().and_then(|name| meta.find_command(name)),
+                _ => None,
+            };
+            match command {
+                Some(Item::Command {
+                    name,
+                    meta: cmd_meta,
+                    info: cmd_info,
+                    ..
+                }) => {
+                    path.push((*name).to_owned());
+                    info = cmd_info.as_ref().clone();
+                    meta = cmd_meta.as_ref().clone();
+                }
+                _ => return Some(Message::Unconsumed(ix).render(args, &meta)),
+            }
+        }
+
+        let help_meta = if path.len() == args.path.len() {
+            self.help_meta(&args.globals)
+        } else {
+            info.meta()
+        };
+        let buffer = render_help(&path, &info, &meta, &help_meta, true);
+        Some(ParseFailure::Stdout(buffer, false))
+    }
+
+    /// Help and version items followed by global items inherited from the parents
+    fn help_meta(&self, globals: &[GlobalItem]) -> Meta {
+        let help = self.info.meta();
+        if globals.is_empty() {
+            return help;
+        }
+        let globals = Meta::And(globals.iter().map(GlobalItem::meta).collect());
+        Meta::And(vec![
+            help,
+            Meta::Subsection(
+                Box::new(globals),
+                Header::from(Doc::from("Global options:")),
+            ),
+        ])
+    }
+
     /// Run subparser, implementation detail
     pub(crate) fn run_subparser(&self, args: &mut State) -> Result<T, ParseFailure> {
         // process should work like this:
@@ -240,22 +429,39 @@ impl<T> OptionParser<T> {
         //
         // outer parser gets value in ParseFailure format
 
+        // global items registered by the parents, own global items are already in inner meta
+        let globals = args.globals.clone();
+
         if self.info.help_if_no_args && args.is_empty() {
             let buffer = render_help(
                 &args.path,
                 &self.info,
                 &self.inner.meta(),
-                &self.info.meta(),
+                &self.help_meta(&globals),
                 true,
             );
             return Err(ParseFailure::Stdout(buffer, false));
         };
 
+        if let Some(failure) = self.help_for_command_path(args) {
+            return Err(failure);
+        }
+
+        let default_command = self.pick_default_command(args);
+        let parent_default = std::mem::replace(&mut args.default_command, default_command);
         let res = self.inner.eval(args);
+        args.default_command = parent_default;
         if let Err(Error(Message::ParseFailure(failure))) = res {
             return Err(failure);
         }
         #[cfg(feature = "autocomplete")]
+        if args.completing_named() {
+            self.info.push_completions(args);
+            for global in &globals {
+                let _ = global.eval(args);
+            }
+        }
+        #[cfg(feature = "autocomplete")]
         if let Some(comp) = args.check_complete() {
             return Err(ParseFailure::Completion(comp));
         }
@@ -281,7 +487,7 @@ impl<T> OptionParser<T> {
                         &args.path,
                         &self.info,
                         &self.inner.meta(),
-                        &self.info.meta(),
+                        &self.help_meta(&globals),
                         true,
                     )
                 }
@@ -292,11 +498,33 @@ impl<T> OptionParser<T> {
                     buffer.text("Version: ");
                     buffer.doc(&v);
                     buffer.token(Token::BlockEnd(Block::Block));
+                    if let Some(features) = &self.info.version_features {
+                        buffer.token(Token::BlockStart(Block::Block));
+                        buffer.text("Features: ");
+                        if features.is_empty() {
+                            buffer.text("none");
+                        } else {
+                            buffer.text(&features.join(", "));
+                        }
+                        buffer.token(Token::BlockEnd(Block::Block));
+                    }
                     buffer
                 }
             };
             return Err(ParseFailure::Stdout(buffer, detailed));
         }
+
+        // parser asked to show the help message
+        if let Message::ShowHelp = err {
+            let buffer = render_help(
+                &args.path,
+                &self.info,
+                &self.inner.meta(),
+                &self.help_meta(&globals),
+                true,
+            );
+            return Err(ParseFailure::Stdout(buffer, false));
+        }
         Err(err.render(args, &self.inner.meta()))
     }
 
@@ -349,6 +577,37 @@ impl<T> OptionParser<T> {
         self.info.version = Some(version.into());
         self
     }
+
+    /// List enabled compile time features in the `--version` output
+    ///
+    /// `bpaf` can't tell which features were enabled for your crate so the list should be
+    /// collected by the caller, usually with [`cfg!`]: `cfg!(feature = "tls")`. Features are
+    /// printed in the order given after the version, empty list is printed as `none`. Without
+    /// this call the `--version` output contains only the version. Has no effect unless
+    /// [`version`](OptionParser::version) is set.
+    ///
+    /// ```rust
+    /// # use bpaf::*;
+    /// let features = [("color", false), ("tls", true), ("zstd", true)]
+    ///     .iter()
+    ///     .filter_map(|(name, enabled)| if *enabled { Some(*name) } else { None })
+    ///     .collect::<Vec<_>>();
+    ///
+    /// let parser = short('s')
+    ///     .switch()
+    ///     .to_options()
+    ///     .version("0.5.0")
+    ///     .version_features(&features);
+    ///
+    /// let r = parser.run_inner(&["--version"]).unwrap_err().unwrap_stdout();
+    /// assert_eq!(r, "Version: 0.5.0\n\nFeatures: tls, zstd\n");
+    /// ```
+    #[must_use]
+    pub fn version_features(mut self, features: &[&str]) -> Self {
+        self.info.version_features = Some(features.iter().map(|f| (*f).to_owned()).collect());
+        self
+    }
+
     /// Set the description field
     ///
     /// Description field should be 1-2 lines long briefly explaining program purpose. If
@@ -545,6 +804,82 @@ impl<T> OptionParser<T> {
         self
     }
 
+    /// Add a usage example to the help message
+    ///
+    /// Examples are listed in the order they are added in a separate "Examples:" section after
+    /// the options and commands and before the [`footer`](OptionParser::footer). `invocation` is
+    /// shown verbatim as a literal, so it should include the program name, `description`
+    /// explains what it does. Examples are also included in the markdown, html and manpage
+    /// documentation. For examples that belong to a single option use its help message instead.
+    ///
+    /// ```rust
+    /// # use bpaf::*;
+    /// let release = long("release").help("Build in release mode").switch();
+    /// let parser = release
+    ///     .to_options()
+    ///     .example("mytool --release", "Build an optimized binary")
+    ///     .example("mytool", "Build a debug binary");
+    /// let r = parser.run_inner(&["--help"]).unwrap_err().unwrap_stdout();
+    /// assert!(r.contains("Examples:\n    mytool --release  Build an optimized binary\n"));
+    /// ```
+    #[must_use]
+    pub fn example<M: Into<Doc>>(mut self, invocation: &str, description: M) -> Self {
+        self.info
+            .examples
+            .push((invocation.to_owned(), description.into()));
+        self
+    }
+
+    /// Append a list of available commands to the footer
+    ///
+    /// Footer gets a section titled with `hint` that lists every command available at this level
+    /// along with the first line of its help message, any footer set with
+    /// [`footer`](OptionParser::footer) stays in front of it. List is generated from the parser
+    /// so it stays up to date as commands are added or removed, hidden commands are not listed.
+    /// Parsers without commands are left unchanged.
+    ///
+    /// ```rust
+    /// # use bpaf::*;
+    /// let add = pure(())
+    ///     .to_options()
+    ///     .descr("Add a new item\nItem is added to the end of the list")
+    ///     .command("add");
+    /// let remove = pure(())
+    ///     .to_options()
+    ///     .descr("Remove an item")
+    ///     .command("remove");
+    /// let parser = construct!([add, remove])
+    ///     .to_options()
+    ///     .with_help_footer_from_commands("Run `app COMMAND --help` for more information:");
+    ///
+    /// let r = parser.run_inner(&["--help"]).unwrap_err().unwrap_stdout();
+    /// assert!(r.ends_with(
+    ///     "\
+    /// Run `app COMMAND --help` for more information:
+    ///     add         Add a new item
+    ///     remove      Remove an item
+    /// "
+    /// ));
+    /// ```
+    #[must_use]
+    pub fn with_help_footer_from_commands(mut self, hint: &str) -> Self {
+        let mut buf = Doc::default();
+        buf.write_command_summary(&self.inner.meta(), hint);
+        if buf.is_empty() {
+            return self;
+        }
+        if let Some(footer) = self.info.footer.take() {
+            let mut res = Doc::default();
+            res.token(Token::BlockStart(Block::Block));
+            res.doc(&footer);
+            res.token(Token::BlockEnd(Block::Block));
+            res.doc(&buf);
+            buf = res;
+        }
+        self.info.footer = Some(buf);
+        self
+    }
+
     /// Set custom usage field
     ///
     /// Custom usage field to use instead of one derived by `bpaf`.
@@ -594,6 +929,38 @@ impl<T> OptionParser<T> {
         self
     }
 
+    /// Render the usage line on its own
+    ///
+    /// Returns the same usage line `bpaf` prints as part of `--help` output: either one derived
+    /// automatically or one set with [`usage`](OptionParser::usage) or
+    /// [`with_usage`](OptionParser::with_usage). Result is a plain text without any styling, same
+    /// as one you would get from [`Doc::monochrome`]. Program name in the automatically derived
+    /// usage comes from [`name`](OptionParser::name) if set, otherwise from the name of the
+    /// running executable, same as with [`run`](OptionParser::run).
+    ///
+    /// This can be useful when you want to display a terse reminder of how to invoke the app,
+    /// say in a custom error handler, without printing the full help message.
+    ///
+    /// ```rust
+    /// # use bpaf::*;
+    /// let verbose = short('v').long("verbose").switch();
+    /// let name = positional::<String>("NAME");
+    /// let parser = construct!(verbose, name).to_options().name("app");
+    ///
+    /// assert_eq!(parser.render_usage(), "Usage: app [-v] NAME");
+    /// ```
+    #[must_use]
+    pub fn render_usage(&self) -> String {
+        let name = match self.info.name {
+            Some(name) => Some(name.to_owned()),
+            None => Args::current_args().name,
+        };
+        let path = name.into_iter().collect::<Vec<_>>();
+        let mut buf = Doc::default();
+        buf.write_usage(&path, &self.info, &self.inner.meta());
+        buf.render_console(true, Color::Monochrome, self.info.max_width)
+    }
+
     /// Check the invariants `bpaf` relies on for normal operations
     ///
     /// Takes a parameter whether to check for cosmetic invariants or not
@@ -611,9 +978,18 @@ impl<T> OptionParser<T> {
     ///
     /// # Panics
     ///
-    /// `check_invariants` indicates problems with panic
+    /// `check_invariants` indicates problems with panic, currently it checks that
+    ///
+    /// - positional items and commands are placed in the right most position of the structure
+    /// - every [`adjacent`](crate::ParseCon::adjacent) block starts with an item it can be
+    ///   anchored to: a named item, a positional or a command, not a hidden or
+    ///   [`pure`](crate::pure) parser. The item can be a [`switch`](NamedArg::switch) or a set of
+    ///   alternatives where every branch starts with such an item, but not an optional argument
+    ///   or positional
     pub fn check_invariants(&self, _cosmetic: bool) {
-        self.inner.meta().positional_invariant_check(true);
+        let meta = self.inner.meta();
+        meta.positional_invariant_check(true);
+        meta.adjacent_invariant_check();
     }
 
     /// Customize parser for `--help`
@@ -649,6 +1025,9 @@ impl<T> OptionParser<T> {
     /// and user specified none - it might be a better experience for user to print
     /// the help message.
     ///
+    /// To show the help message in other situations, for example when some items are
+    /// present but not the subcommand, use [`fail_with_help`](crate::fail_with_help).
+    ///
     /// ```rust
     /// # use bpaf::*;
     /// # fn options() -> OptionParser<bool> { short('a').switch().to_options() }
@@ -696,11 +1075,417 @@ impl<T> OptionParser<T> {
     ///
     /// Setting the max width too low may negatively affect the readability of the help message.
     /// Also, the alignment padding of broken lines is always applied.
+    ///
+    /// Width only affects messages printed by [`run`](OptionParser::run), messages produced by
+    /// [`run_inner`](OptionParser::run_inner) for tests are always wrapped at 100 characters.
     #[must_use]
     pub fn max_width(mut self, width: usize) -> Self {
         self.info.max_width = width;
         self
     }
+
+    /// List required options separately from the rest in the help message
+    ///
+    /// By default all the named items are listed under "Available options:". With this enabled
+    /// options that must be present are listed first, under "Required options:", and everything
+    /// else stays in "Available options:". An option is required unless it is made
+    /// [`optional`](Parser::optional), has a [`fallback`](Parser::fallback), can be
+    /// repeated zero or more times with [`many`](Parser::many) or is one of several alternatives.
+    /// Flags created with [`switch`](NamedArg::switch) have a fallback, help and version flags
+    /// are never required. Custom sections made with [`group_help`](Parser::group_help) and
+    /// options in adjacent blocks are not split.
+    ///
+    /// ```rust
+    /// # use bpaf::*;
+    /// let input = long("input").help("Input file").argument::<String>("FILE");
+    /// let verbose = short('v').help("Be verbose").switch();
+    /// let parser = construct!(input, verbose).to_options().split_required(true);
+    /// let r = parser.run_inner(&["--help"]).unwrap_err().unwrap_stdout();
+    /// assert!(r.contains("Required options:\n        --input=FILE  Input file\n"));
+    /// ```
+    #[must_use]
+    pub fn split_required(mut self, split: bool) -> Self {
+        self.info.split_required = split;
+        self
+    }
+
+    /// Show help message using a pager
+    ///
+    /// When enabled and `stdout` is a terminal [`run`](OptionParser::run) pipes the help message
+    /// through a pager instead of printing it directly, `--version` output is printed as usual.
+    /// Pager command comes from `PAGER` environment variable and can contain extra arguments,
+    /// `less` is used if `PAGER` is not set and an empty `PAGER` disables paging. If `LESS`
+    /// environment variable is not set `bpaf` sets it to `FRX` so short messages are printed
+    /// without waiting for the user to quit the pager.
+    ///
+    /// If pager can't be started or exits with a failure `bpaf` prints the message as usual.
+    /// Paged message is rendered without colors. Checking if `stdout` is a terminal requires
+    /// Rust 1.70 or newer, with older compilers help is never paged.
+    ///
+    /// ```rust
+    /// # use bpaf::*;
+    /// # fn options() -> OptionParser<bool> { short('a').switch().to_options() }
+    /// let opts = options().page_help(true).run();
+    /// # drop(opts);
+    /// ```
+    #[must_use]
+    pub fn page_help(mut self, page: bool) -> Self {
+        self.info.page_help = page;
+        self
+    }
+
+    /// Use colors for help and version messages
+    ///
+    /// With default [`ColorChoice::Auto`] [`run`](OptionParser::run) uses colors if `stdout`
+    /// supports them and `NO_COLOR` environment variable is not set, explicit
+    /// [`ColorChoice::Always`] and [`ColorChoice::Never`] take priority over `NO_COLOR`. Colors
+    /// are only available with one of the `color` cargo features enabled. To control colors for
+    /// error messages use [`color_error`](OptionParser::color_error).
+    ///
+    /// ```rust
+    /// # use bpaf::*;
+    /// # fn options() -> OptionParser<bool> { short('a').switch().to_options() }
+    /// let opts = options()
+    ///     .color_help(ColorChoice::Auto)
+    ///     .color_error(ColorChoice::Never)
+    ///     .run();
+    /// # drop(opts);
+    /// ```
+    #[must_use]
+    pub fn color_help(mut self, choice: ColorChoice) -> Self {
+        self.info.color_help = choice;
+        self
+    }
+
+    /// Use colors for error messages
+    ///
+    /// Same as [`color_help`](OptionParser::color_help), but for errors printed to `stderr`, with
+    /// [`ColorChoice::Auto`] `bpaf` checks if `stderr` supports colors.
+    #[must_use]
+    pub fn color_error(mut self, choice: ColorChoice) -> Self {
+        self.info.color_error = choice;
+        self
+    }
+
+    /// Parse extra items as if user passed them before the real command line
+    ///
+    /// Useful for wrapper binaries that want to pass some defaults to the parser while still
+    /// letting the user to override them. Items are added in front of the items passed to
+    /// [`run`](OptionParser::run) or [`run_inner`](OptionParser::run_inner), so for parsers
+    /// that accept several values such as [`last`](Parser::last) or [`many`](Parser::many) values
+    /// from the real command line come after the prepended ones and take priority with `last`.
+    /// Parsers that accept only a single value such as [`argument`](NamedArg::argument) or
+    /// [`switch`](NamedArg::switch) fail if the same item is present in both places.
+    ///
+    /// Prepended items are not visible in `--help` and only apply to the top level parser,
+    /// [`fallback_to_usage`](OptionParser::fallback_to_usage) stops working if there are any.
+    /// Prepended and real items are parsed separately when it comes to `--`: `--` among the
+    /// prepended items makes only the prepended items after it positional, real command line
+    /// including its own `--` and shell completion is handled the same way as without
+    /// `prepend_args`.
+    ///
+    /// ```rust
+    /// # use bpaf::*;
+    /// # use std::ffi::OsString;
+    /// let jobs = long("jobs").argument::<usize>("N").last();
+    /// let parser = jobs
+    ///     .to_options()
+    ///     .prepend_args(&[OsString::from("--jobs"), OsString::from("4")]);
+    ///
+    /// let r = parser.run_inner(&[]).unwrap();
+    /// assert_eq!(r, 4);
+    ///
+    /// let r = parser.run_inner(&["--jobs", "8"]).unwrap();
+    /// assert_eq!(r, 8);
+    /// ```
+    #[must_use]
+    pub fn prepend_args(mut self, args: &[OsString]) -> Self {
+        self.info.prepend_args = args.to_vec();
+        self
+    }
+
+    /// Run a named command if user didn't specify any
+    ///
+    /// For an application where `mytool` alone should behave like `mytool status` you can
+    /// designate `status` as a default command: if none of the commands available at this level
+    /// are present on the command line `bpaf` runs the parser for the default command as if its
+    /// name was given. Flags that belong to the parent parser are consumed as usual so
+    /// `mytool --verbose` behaves like `mytool --verbose status`, and any remaining items are
+    /// passed to the default command, errors are reported from its context.
+    ///
+    /// `name` must match the first name given to [`command`](OptionParser::command).
+    ///
+    /// Default command doesn't take over `--help` and `--version` for the parent parser or shell
+    /// completion for command names. If [`fallback_to_usage`](OptionParser::fallback_to_usage) is
+    /// also set - invoking the application without any arguments prints the help message instead
+    /// of running the default command, other invocations without a command still use the default.
+    ///
+    /// ```rust
+    /// # use bpaf::*;
+    /// #[derive(Debug, Clone, PartialEq)]
+    /// enum Cmd {
+    ///     Status { brief: bool },
+    ///     Log { count: usize },
+    /// }
+    ///
+    /// let brief = short('s').switch();
+    /// let status = construct!(Cmd::Status { brief })
+    ///     .to_options()
+    ///     .command("status");
+    /// let count = short('n').argument::<usize>("N").fallback(10);
+    /// let log = construct!(Cmd::Log { count }).to_options().command("log");
+    /// let parser = construct!([status, log])
+    ///     .to_options()
+    ///     .default_command("status");
+    ///
+    /// let r = parser.run_inner(&[]).unwrap();
+    /// assert_eq!(r, Cmd::Status { brief: false });
+    ///
+    /// let r = parser.run_inner(&["-s"]).unwrap();
+    /// assert_eq!(r, Cmd::Status { brief: true });
+    ///
+    /// let r = parser.run_inner(&["log", "-n", "3"]).unwrap();
+    /// assert_eq!(r, Cmd::Log { count: 3 });
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if `name` is not a command available at this level
+    #[must_use]
+    pub fn default_command(mut self, name: &'static str) -> Self {
+        match self.inner.meta().find_command(name) {
+            Some(Item::Command { name: n, .. }) if *n == name => {}
+            _ => panic!(
+                "bpaf usage BUG: default_command {:?} must be a command available at this level",
+                name
+            ),
+        }
+        self.info.default_command = Some(name);
+        self
+    }
+
+    /// Set the program name used in usage lines and help messages
+    ///
+    /// By default [`run`](OptionParser::run) takes the program name from the file name of the
+    /// executable (`argv[0]`), which might not be what users type: cargo subcommands are invoked
+    /// as `cargo mytool` and executables can be renamed or invoked via symlinks. Name set
+    /// with this method takes precedence over `argv[0]` and over the name passed with
+    /// [`Args::set_name`]. It only applies to the top level parser, subcommands are always
+    /// shown with their command names.
+    ///
+    /// Shell completion scripts generated with `--bpaf-complete-style-*` still use the name of
+    /// the executable so the shell can invoke it.
+    ///
+    /// ```rust
+    /// # use bpaf::*;
+    /// let parser = short('v')
+    ///     .switch()
+    ///     .to_options()
+    ///     .name("mytool");
+    /// let r = parser
+    ///     .run_inner(Args::from(&["--help"]).set_name("mytool-x86_64"))
+    ///     .unwrap_err()
+    ///     .unwrap_stdout();
+    /// assert!(r.starts_with("Usage: mytool [-v]"));
+    /// ```
+    ///
+    /// # Derive usage
+    ///
+    /// `name` annotation is available after `options` annotation, takes the name to use.
+    ///
+    /// ```rust
+    /// # use bpaf::*;
+    /// #[derive(Debug, Clone, Bpaf)]
+    /// #[bpaf(options, name("mytool"))]
+    /// struct Options {
+    ///     verbose: bool,
+    /// }
+    /// ```
+    #[must_use]
+    pub fn name(mut self, name: &'static str) -> Self {
+        self.info.name = Some(name);
+        self
+    }
+
+    /// Add a built-in `help` command that renders help for a command path
+    ///
+    /// With this enabled `mytool help build deploy` prints the same help message as
+    /// `mytool build deploy --help` and `mytool help` prints the help for `mytool` itself.
+    /// `help` shows up in the list of available commands. `--help` keeps working as before,
+    /// both at the top level and inside subcommands.
+    ///
+    /// Built-in command is only recognized as the first item at the level where it is enabled
+    /// and takes precedence over the parser itself, so a positional value or a command called
+    /// `help` at this level becomes unreachable. Nested command parsers don't get their own
+    /// `help` command unless it is enabled on them as well. Unknown command names in the
+    /// path produce the usual "not expected in this context" error.
+    ///
+    /// ```rust
+    /// # use bpaf::*;
+    /// let deploy = pure(()).to_options().descr("Deploy the thing").command("deploy");
+    /// let build = deploy.to_options().descr("Build the thing").command("build");
+    /// let parser = build.to_options().help_command();
+    ///
+    /// let r = parser
+    ///     .run_inner(&["help", "build", "deploy"])
+    ///     .unwrap_err()
+    ///     .unwrap_stdout();
+    /// assert!(r.starts_with("Deploy the thing"));
+    /// ```
+    #[must_use]
+    pub fn help_command(mut self) -> Self {
+        self.info.help_command = true;
+        self
+    }
+
+    /// Transform or validate the final result after parsing succeeds
+    ///
+    /// Function runs once the whole command line is consumed, so it doesn't interfere with
+    /// `--help`, `--version` or shell completion. If the function fails `bpaf` reports the error
+    /// message as is, the same way as other parsing failures: printed to `stderr` by
+    /// [`run`](OptionParser::run) or returned as [`ParseFailure::Stderr`] by
+    /// [`run_inner`](OptionParser::run_inner).
+    ///
+    /// Unlike [`Parser::parse`] this works on the finalized [`OptionParser`], so you can use it for
+    /// post processing of the whole result without changing the parser itself: canonicalizing
+    /// paths, applying overrides from the environment, checking consistency of the whole
+    /// configuration.
+    ///
+    /// ```rust
+    /// # use bpaf::*;
+    /// #[derive(Debug, Clone)]
+    /// struct Range {
+    ///     from: u32,
+    ///     to: u32,
+    /// }
+    ///
+    /// let from = long("from").argument::<u32>("N");
+    /// let to = long("to").argument::<u32>("N");
+    /// let parser = construct!(Range { from, to })
+    ///     .to_options()
+    ///     .then(|r| {
+    ///         if r.from <= r.to {
+    ///             Ok(r)
+    ///         } else {
+    ///             Err(format!("{} is bigger than {}", r.from, r.to))
+    ///         }
+    ///     });
+    ///
+    /// let r = parser.run_inner(&["--from", "5", "--to", "1"]).unwrap_err().unwrap_stderr();
+    /// assert_eq!(r, "5 is bigger than 1");
+    /// ```
+    #[must_use]
+    pub fn then<F>(self, f: F) -> Self
+    where
+        F: Fn(T) -> Result<T, String> + 'static,
+        T: 'static,
+    {
+        Self {
+            inner: Box::new(ParseThen {
+                inner: self.inner,
+                f,
+            }),
+            info: self.info,
+        }
+    }
+}
+
+/// Pick a pager command and its arguments from the value of `PAGER`, `None` disables paging
+pub(crate) fn pager_command(pager: Option<&str>) -> Option<Vec<&str>> {
+    let words = pager
+        .unwrap_or("less")
+        .split_whitespace()
+        .collect::<Vec<_>>();
+    if words.is_empty() {
+        None
+    } else {
+        Some(words)
+    }
+}
+
+/// Try to display a message with a pager, returns `false` if pager can't be used or fails
+pub(crate) fn page_message(msg: &Doc, full: bool, max_width: usize, pager: Option<&str>) -> bool {
+    use std::io::Write;
+    use std::process::{Command, Stdio};
+
+    let words = match pager_command(pager) {
+        Some(words) => words,
+        None => return false,
+    };
+    let mut command = Command::new(words[0]);
+    command.args(&words[1..]).stdin(Stdio::piped());
+    if std::env::var_os("LESS").is_none() {
+        command.env("LESS", "FRX");
+    }
+
+    let mut child = match command.spawn() {
+        Ok(child) => child,
+        Err(_) => return false,
+    };
+    if let Some(mut stdin) = child.stdin.take() {
+        let text = msg.render_console(full, Color::Monochrome, max_width);
+        // pager can exit before reading everything, nothing to do about it
+        let _ = writeln!(stdin, "{}", text);
+    }
+    match child.wait() {
+        Ok(status) => status.success(),
+        Err(_) => false,
+    }
+}
+
+/// `--version` is reported the same way as help, but it's short and is never paged
+pub(crate) fn is_version(msg: &Doc) -> bool {
+    msg.monochrome(false).starts_with("Version: ")
+}
+
+// only compiled with Rust versions that have IsTerminal, see build.rs
+#[cfg(bpaf_is_terminal)]
+#[allow(clippy::incompatible_msrv)]
+fn stdout_is_terminal() -> bool {
+    use std::io::IsTerminal;
+    std::io::stdout().is_terminal()
+}
+
+#[cfg(not(bpaf_is_terminal))]
+fn stdout_is_terminal() -> bool {
+    false
+}
+
+/// Applies a final transformation to the result of [`OptionParser`], created with
+/// [`OptionParser::then`]
+struct ParseThen<T, F> {
+    inner: Box<dyn Parser<T>>,
+    f: F,
+}
+
+impl<T, F> Parser<T> for ParseThen<T, F>
+where
+    F: Fn(T) -> Result<T, String>,
+{
+    fn eval(&self, args: &mut State) -> Result<T, Error> {
+        let res = self.inner.eval(args)?;
+
+        // leftover items can be --help, --version or something invalid, completion needs to
+        // see the original result too, in all those cases it's not a final result yet.
+        #[cfg(feature = "autocomplete")]
+        let completing = args.comp_ref().is_some();
+        #[cfg(not(feature = "autocomplete"))]
+        let completing = false;
+        if !args.is_empty() || completing {
+            return Ok(res);
+        }
+
+        (self.f)(res).map_err(|msg| {
+            Error(Message::ParseFailure(ParseFailure::Stderr(
+                Doc::from(msg.as_str()),
+                crate::error::error_code(),
+            )))
+        })
+    }
+
+    fn meta(&self) -> Meta {
+        self.inner.meta()
+    }
 }
 
 impl Info {
@@ -712,6 +1497,19 @@ impl Info {
     fn mk_version_parser(&self) -> impl Parser<()> {
         self.version_arg.clone().req_flag(())
     }
+
+    /// Offer help and version flags as completion candidates
+    ///
+    /// Only used when user started typing a flag name so they don't clutter the list of
+    /// everything else available
+    #[cfg(feature = "autocomplete")]
+    fn push_completions(&self, args: &mut State) {
+        // evaluating the parsers is enough to register the candidates, results are not needed
+        let _ = self.mk_help_parser().eval(args);
+        if self.version.is_some() {
+            let _ = self.mk_version_parser().eval(args);
+        }
+    }
 }
 
 impl Parser<ExtraParams> for Info {
@@ -732,10 +1530,22 @@ impl Parser<ExtraParams> for Info {
     }
 
     fn meta(&self) -> Meta {
-        let help = self.mk_help_parser().meta();
-        match &self.version {
-            Some(_) => Meta::And(vec![help, self.mk_version_parser().meta()]),
-            None => help,
+        let mut meta = vec![self.mk_help_parser().meta()];
+        if self.version.is_some() {
+            meta.push(self.mk_version_parser().meta());
+        }
+        if self.help_command {
+            meta.push(Meta::from(Item::Command {
+                name: "help",
+                short: None,
+                help: Some(Doc::from("Print help for a command")),
+                meta: Box::new(Meta::Skip),
+                info: Box::default(),
+            }));
+        }
+        match meta.len() {
+            1 => meta.remove(0),
+            _ => Meta::And(meta),
         }
     }
 }
@@ -1,4 +1,4 @@
-use crate::{info::Info, meta_help::Metavar, parsers::NamedArg, Doc, Meta};
+use crate::{info::Info, meta_help::Metavar, params::HelpSep, parsers::NamedArg, Doc, Meta};
 
 #[doc(hidden)]
 #[derive(Clone, Debug)]
@@ -26,6 +26,8 @@ pub enum Item {
         name: ShortLong,
         /// used for disambiguation
         shorts: Vec<char>,
+        /// all the long names including hidden aliases, used for error messages
+        longs: Vec<&'static str>,
         env: Option<&'static str>,
         help: Option<Doc>,
     },
@@ -36,9 +38,13 @@ pub enum Item {
         name: ShortLong,
         /// used for disambiguation
         shorts: Vec<char>,
+        /// all the long names including hidden aliases, used for error messages
+        longs: Vec<&'static str>,
         metavar: Metavar,
         env: Option<&'static str>,
         help: Option<Doc>,
+        /// separator between the name and the metavar in help
+        sep: HelpSep,
     },
 }
 
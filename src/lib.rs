@@ -2,7 +2,7 @@
 #![allow(clippy::needless_doctest_main)]
 #![allow(clippy::redundant_else)] // not useful
 #![allow(rustdoc::redundant_explicit_links)] // two random markdown parsers I tried only supports explicit links
-#![cfg_attr(docsrs, feature(doc_auto_cfg))]
+#![cfg_attr(docsrs, feature(doc_cfg))]
 
 //! Lightweight and flexible command line argument parser with derive and combinatoric style API
 
@@ -83,6 +83,10 @@
 //!   [`catch`](ParseMany::catch), [`catch`](ParseSome::catch) and [`catch`](ParseCollect::catch).
 //! - [`map`](Parser::map), [`parse`](Parser::parse) and [`guard`](Parser::guard) - transform
 //!   and/or validate value produced by a parser
+//! - [`memoize`](Parser::memoize) - reuse a value produced by an expensive parser when it is
+//!   evaluated more than once on the same input
+//! - [`conflicts_with`](Parser::conflicts_with) - reject the command line if the parser is used
+//!   together with some other named item
 //! - [`to_options`](Parser::to_options) - finalize the parser and prepare to run it
 //!
 //! ## Combining multiple parsers together
@@ -97,6 +101,8 @@
 //!
 //! You can use [`adjacent`](ParseCon::adjacent) annotation to parse multiple flags as an adjacent
 //! group allowing for more unusual scenarios such as multiple value arguments or chained commands.
+//! [`grouped_by`](ParseCon::grouped_by) splits the arguments into records that start with a
+//! delimiter flag instead.
 //!
 //! ## Improving user experience
 //!
@@ -106,9 +112,12 @@
 //!
 //! - [`hide_usage`](Parser::hide_usage) and [`hide`](Parser::hide) - hide the parser from
 //!   generated *Usage* line or whole generated help
-//! - [`group_help`](Parser::group_help) and [`with_group_help`](Parser::with_group_help) -
+//! - [`group_help`](Parser::group_help), [`group_help_with`](Parser::group_help_with) and
+//!   [`with_group_help`](Parser::with_group_help) -
 //!   add a common description shared by several parsers
 //! - [`custom_usage`](Parser::custom_usage) - customize usage for a primitive or composite parser
+//! - [`env_group`](Parser::env_group) - take values for a group of parsers from environment
+//!   variables only and list those variables in a separate help section
 //! - [`usage`](OptionParser::usage) and [`with_usage`](OptionParser::with_usage) lets you to
 //!   customize whole usage line as a whole either by completely overriding it or by building around it.
 //!
@@ -141,6 +150,8 @@
 //!
 //!  - `batteries`: helpers implemented with public `bpaf` API. Disabled by default.
 //!
+//!  - `encoding`: hex and base64 decoders in `batteries`, enables `batteries`. Disabled by default.
+//!
 //!  - `autocomplete`: enables support for shell autocompletion. Disabled by default.
 //!
 //!
@@ -155,7 +166,7 @@
 //!    ```
 //!    Disabled by default.
 //!
-//!  - `docgen`: generate documentation from help declaration, see [`OptionParser::render_markdown`] and [`doc`](crate::doc). Disabled by default.
+//!  - `docgen`: generate documentation from help declaration, see [`OptionParser::render_markdown`], [`OptionParser::render_dot`] and [`doc`](crate::doc). Disabled by default.
 
 
 
@@ -198,35 +209,47 @@ pub mod parsers {
     pub use crate::complete_shell::ParseCompShell;
     #[doc(inline)]
     pub use crate::params::{
-        NamedArg, ParseAny, ParseArgument, ParseCommand, ParseFlag, ParsePositional,
+        NamedArg, ParseAny, ParseArgument, ParseArgumentOrStdin, ParseCommand, ParseFlag,
+        ParsePositional, ParsePositionalStream, ParseRest, ParseThen,
     };
+    #[cfg(feature = "autocomplete")]
+    #[doc(inline)]
+    pub use crate::structs::ParseComp;
     #[doc(inline)]
     pub use crate::structs::{
-        ParseCollect, ParseCon, ParseCount, ParseFallback, ParseFallbackWith, ParseLast, ParseMany,
-        ParseOptional, ParseSome,
+        AtMostOneOf, ParseCollect, ParseCon, ParseCount, ParseFallback, ParseFallbackWith,
+        ParseLast, ParseMany, ParseOptional, ParseSome,
     };
 }
 
 // -------------------------------------------------------------------
 
 #[doc(inline)]
-pub use crate::{args::Args, buffer::Doc, error::ParseFailure, info::OptionParser};
+pub use crate::{
+    args::Args,
+    buffer::{ColorChoice, Doc},
+    error::ParseFailure,
+    info::OptionParser,
+    params::{FileOrStdin, HelpSep, PositionalStream, ValueSpec},
+};
 
 #[doc(hidden)]
 // used by construct macro, not part of public API
 pub use crate::{args::State, error::Error, meta::Meta, structs::ParseCon};
 
-use std::{marker::PhantomData, str::FromStr};
+use std::{cell::RefCell, marker::PhantomData, rc::Rc, str::FromStr};
 
 use crate::{
     buffer::{MetaInfo, Style},
     item::Item,
     params::build_positional,
-    parsers::{NamedArg, ParseAny, ParseCommand, ParsePositional},
+    parsers::{NamedArg, ParseAny, ParseCommand, ParsePositional, ParsePositionalStream},
     structs::{
-        ParseCollect, ParseCount, ParseFail, ParseFallback, ParseFallbackWith, ParseGroupHelp,
-        ParseGuard, ParseHide, ParseLast, ParseMany, ParseMap, ParseOptional, ParseOrElse,
-        ParsePure, ParsePureWith, ParseSome, ParseUsage, ParseWith, ParseWithGroupHelp,
+        AtMostOneOf, GlobalItem, ParseCollect, ParseConflicts, ParseCount, ParseEnvGroup,
+        ParseFail, ParseFailWithHelp, ParseFallback, ParseFallbackWith, ParseGlobal,
+        ParseGroupHelp, ParseGroupHelpWith, ParseGuard, ParseHide, ParseLast, ParseMany, ParseMap,
+        ParseMemoize, ParseOptional, ParseOrElse, ParsePure, ParsePureWith, ParseSome, ParseUsage,
+        ParseWith, ParseWithGroupHelp,
     },
 };
 
@@ -548,6 +571,33 @@ macro_rules! construct {
 /// }
 /// ```
 ///
+/// With derive API `guard` annotation at the struct or enum level validates the whole
+/// value after all the fields are parsed, there's no need to write a `construct!` by hand:
+///
+/// ```rust
+/// # use bpaf::*;
+/// fn valid_side(side: &u32) -> bool {
+///     (1..=20).contains(side)
+/// }
+///
+/// fn valid_area(rect: &Rectangle) -> bool {
+///     rect.width * rect.height <= 200
+/// }
+///
+/// #[derive(Debug, Clone, Bpaf)]
+/// #[bpaf(guard(valid_area, "Area of a rectangle must not exceed 200 units square"))]
+/// struct Rectangle {
+///     /// Width of the rectangle
+///     #[bpaf(argument("PX"), guard(valid_side, "Sides of a rectangle must be 1..20 units long"))]
+///     width: u32,
+///     /// Height of the rectangle
+///     #[bpaf(argument("PX"), guard(valid_side, "Sides of a rectangle must be 1..20 units long"))]
+///     height: u32,
+/// }
+/// # let r = rectangle().to_options().run_inner(&["--width", "15", "--height", "15"]);
+/// # assert!(r.is_err());
+/// ```
+///
 ///
 /// # Derive specific considerations
 ///
@@ -787,6 +837,50 @@ pub trait Parser<T> {
             parse_fn: f,
             res: PhantomData,
             err: PhantomData,
+            exact: false,
+        }
+    }
+
+    /// Apply a failing transformation to a contained value, use the error message as is
+    ///
+    /// Works the same way as [`parse`](Parser::parse) except for how failures are reported.
+    /// `parse` puts the error message after a generic prefix that mentions the offending item:
+    /// ``couldn't parse `0`: must be positive``. `parse_exact` uses the [`Display`](std::fmt::Display)
+    /// output of the error as the whole message so a domain specific error type can describe
+    /// the problem in its own words. Same as with `parse` this failure is not recovered by
+    /// [`optional`](Parser::optional), [`many`](Parser::many) and similar combinators.
+    ///
+    /// ```rust
+    /// # use bpaf::*;
+    /// fn port(p: u16) -> Result<u16, String> {
+    ///     if p < 1024 {
+    ///         Err(format!("port {} is reserved, pick one above 1023", p))
+    ///     } else {
+    ///         Ok(p)
+    ///     }
+    /// }
+    ///
+    /// let parser = long("port").argument::<u16>("PORT").parse_exact(port).to_options();
+    ///
+    /// let r = parser.run_inner(&["--port", "8080"]).unwrap();
+    /// assert_eq!(r, 8080);
+    ///
+    /// let r = parser.run_inner(&["--port", "80"]).unwrap_err().unwrap_stderr();
+    /// assert_eq!(r, "port 80 is reserved, pick one above 1023");
+    /// ```
+    fn parse_exact<F, R, E>(self, f: F) -> ParseWith<T, Self, F, E, R>
+    where
+        Self: Sized + Parser<T>,
+        F: Fn(T) -> Result<R, E>,
+        E: std::fmt::Display,
+    {
+        ParseWith {
+            inner: self,
+            inner_res: PhantomData,
+            parse_fn: f,
+            res: PhantomData,
+            err: PhantomData,
+            exact: true,
         }
     }
     // }}}
@@ -841,6 +935,46 @@ pub trait Parser<T> {
     }
     // }}}
 
+    // {{{ conflicts_with
+    /// Fail if both this parser and a named item are present on the command line
+    ///
+    /// Some flags are independent from each other and are optional, but using them together
+    /// makes no sense: `--quiet` and `--verbose`. An enum with [`construct!`] alternatives
+    /// would produce a single value out of the two, `conflicts_with` keeps the parsers as they are
+    /// and only rejects the command line that contains both:
+    /// ``"`--quiet` cannot be used at the same time as `--verbose`"``.
+    ///
+    /// Conflict is detected once this parser succeeds by consuming at least one item: if any
+    /// item in the current scope matches any of the names in `other` - parsing fails, regardless
+    /// of which parser consumes it and in which order they run. Parser that succeeds without
+    /// consuming anything, for example a [`switch`](NamedArg::switch) that is absent, never
+    /// conflicts. Names are compared literally, values given with environment variables are not
+    /// considered. It is enough to declare the conflict on one of the two parsers.
+    ///
+    /// ```rust
+    /// # use bpaf::*;
+    /// let verbose = short('v').long("verbose").switch();
+    /// let quiet = short('q')
+    ///     .long("quiet")
+    ///     .switch()
+    ///     .conflicts_with(short('v').long("verbose"));
+    /// let parser = construct!(verbose, quiet).to_options();
+    ///
+    /// let r = parser.run_inner(&["-v"]).unwrap();
+    /// assert_eq!(r, (true, false));
+    ///
+    /// let r = parser.run_inner(&["--verbose", "-q"]).unwrap_err().unwrap_stderr();
+    /// assert_eq!(r, "`-q` cannot be used at the same time as `--verbose`");
+    /// ```
+    #[must_use]
+    fn conflicts_with(self, other: NamedArg) -> ParseConflicts<Self>
+    where
+        Self: Sized + Parser<T>,
+    {
+        ParseConflicts { inner: self, other }
+    }
+    // }}}
+
     // combine
     // {{{ fallback
     /// Use this value as default if the value isn't present on a command line
@@ -1003,6 +1137,140 @@ pub trait Parser<T> {
     }
     // }}}
 
+    // {{{ env_group
+    /// Take values for all the items in this parser from environment variables only
+    ///
+    /// Named items inside keep their environment variable fallbacks but can't be passed on a
+    /// command line: they are removed from the usage line and from the list of options and
+    /// their environment variables are listed in a separate "Environment variables" section
+    /// instead. Items without an environment variable can't get any values at all so it only
+    /// makes sense to combine it with parsers that use [`env`](NamedArg::env) for every item.
+    ///
+    /// ```rust
+    /// # use bpaf::*;
+    /// let host = long("host")
+    ///     .env("DB_HOST")
+    ///     .help("Database host")
+    ///     .argument::<String>("HOST")
+    ///     .fallback("localhost".to_owned());
+    /// let debug = long("debug").env("DB_DEBUG").help("Log all the queries").switch();
+    /// let db = construct!(host, debug).env_group();
+    /// let verbose = short('v').help("Be verbose").switch();
+    /// let parser = construct!(db, verbose).to_options();
+    ///
+    /// let r = parser.run_inner(&["--help"]).unwrap_err().unwrap_stdout();
+    /// let expected = "\
+    /// Usage: [-v]
+    ///
+    /// Available options:
+    ///     -v            Be verbose
+    ///     -h, --help    Prints help information
+    ///
+    /// Environment variables:
+    ///     DB_HOST=HOST  Database host
+    ///     DB_DEBUG      Log all the queries
+    /// ";
+    /// assert_eq!(r, expected);
+    ///
+    /// // names from inside the group are not accepted on a command line
+    /// let r = parser.run_inner(&["--debug"]).unwrap_err().unwrap_stderr();
+    /// assert_eq!(r, "`--debug` is not expected in this context");
+    /// ```
+    #[must_use]
+    fn env_group(self) -> ParseEnvGroup<Self>
+    where
+        Self: Sized + Parser<T>,
+    {
+        ParseEnvGroup { inner: self }
+    }
+    // }}}
+
+    // {{{ memoize
+    /// Reuse the result of an earlier evaluation of this parser
+    ///
+    /// To produce good error messages `bpaf` evaluates all the branches of alternative parsers
+    /// (see [`or_else`](Parser::or_else)) so a parser can run several times on the same input,
+    /// along with any expensive [`parse`](Parser::parse) or
+    /// [`fallback_with`](Parser::fallback_with) functions it contains. `memoize` remembers
+    /// the value produced by the inner parser together with the arguments it consumed and
+    /// replays both when the parser is evaluated again on the same unconsumed input.
+    ///
+    /// - only successful results are cached, result type must implement [`Clone`]
+    /// - cache is only valid for a single parse invocation: every call to
+    ///   [`run`](OptionParser::run) or [`run_inner`](OptionParser::run_inner) starts with
+    ///   an empty one
+    /// - during shell completion inner parser is evaluated as usual
+    ///
+    /// ```rust
+    /// # use bpaf::*;
+    /// # use std::cell::Cell;
+    /// # use std::rc::Rc;
+    /// let calls = Rc::new(Cell::new(0));
+    /// let c = calls.clone();
+    /// let config = long("config")
+    ///     .argument::<String>("FILE")
+    ///     .parse(move |name| {
+    ///         c.set(c.get() + 1);
+    ///         Ok::<_, String>(name.len())
+    ///     })
+    ///     .memoize();
+    /// let parser = construct!(config).to_options();
+    ///
+    /// let r = parser.run_inner(&["--config", "app.toml"]).unwrap();
+    /// assert_eq!(r, 8);
+    /// assert_eq!(calls.get(), 1);
+    /// ```
+    #[must_use]
+    fn memoize(self) -> ParseMemoize<Self, T>
+    where
+        Self: Sized + Parser<T>,
+        T: Clone,
+    {
+        ParseMemoize {
+            inner: self,
+            cache: RefCell::new(Vec::new()),
+        }
+    }
+    // }}}
+
+    /// Make this parser available inside of subcommands
+    ///
+    /// Items from the parent parser are consumed from the whole command line so
+    /// `app --verbose build` and `app build --verbose` both set the flag defined by `app`.
+    /// With `global` subcommands also list the item in their help message under
+    /// "Global options" and offer it in shell completion, at any level of nesting.
+    ///
+    /// Parsers are evaluated in the order they are given, so global items should come
+    /// before the commands as usual.
+    ///
+    /// ```rust
+    /// # use bpaf::*;
+    /// let release = long("release").help("Build in release mode").switch();
+    /// let build = construct!(release).to_options().command("build");
+    /// let verbose = short('v').long("verbose").help("Print more").switch().global();
+    /// let parser = construct!(verbose, build).to_options();
+    ///
+    /// let r = parser.run_inner(&["-v", "build"]).unwrap();
+    /// assert_eq!(r, (true, false));
+    /// let r = parser.run_inner(&["build", "--release", "-v"]).unwrap();
+    /// assert_eq!(r, (true, true));
+    ///
+    /// let help = parser.run_inner(&["build", "--help"]).unwrap_err().unwrap_stdout();
+    /// assert!(help.contains("Global options"));
+    /// ```
+    #[must_use]
+    fn global(self) -> ParseGlobal<Self>
+    where
+        Self: Sized + Parser<T> + 'static,
+        T: 'static,
+    {
+        let inner = std::rc::Rc::new(self);
+        ParseGlobal {
+            global: GlobalItem::new(inner.clone()),
+            inner,
+        }
+    }
+
     /// Ignore this parser when generating a usage line
     ///
     /// Parsers hidden from usage will still show up in the available arguments list. Best used on
@@ -1054,6 +1322,34 @@ pub trait Parser<T> {
     }
     // }}}
 
+    /// Attach a help message computed by a closure to a complex parser
+    ///
+    /// Works the same way as [`group_help`](Parser::group_help), but `bpaf` calls `f` only
+    /// when it needs to render the message - in help or generated documentation and to label
+    /// the group during shell completion, parsing alone never calls it.
+    ///
+    /// ```rust
+    /// # use bpaf::*;
+    /// let width = long("width").argument::<u32>("W");
+    /// let height = long("height").argument::<u32>("H");
+    /// let parser = construct!(width, height)
+    ///     .group_help_with(|| Doc::from("Takes a rectangle"))
+    ///     .to_options();
+    ///
+    /// let help = parser.run_inner(&["--help"]).unwrap_err().unwrap_stdout();
+    /// assert!(help.contains("Takes a rectangle"));
+    /// ```
+    fn group_help_with<F>(self, f: F) -> ParseGroupHelpWith<Self, F>
+    where
+        Self: Sized + Parser<T>,
+        F: Fn() -> Doc + 'static,
+    {
+        ParseGroupHelpWith {
+            inner: self,
+            f: Rc::new(f),
+        }
+    }
+
     /// Make a help message for a complex parser from its [`MetaInfo`]
     ///
     #[cfg_attr(not(doctest), doc = include_str!("docs2/with_group_help.md"))]
@@ -1289,9 +1585,103 @@ pub fn fail<T>(msg: &'static str) -> ParseFail<T> {
     ParseFail {
         field1: msg,
         field2: PhantomData,
+        code: None,
+    }
+}
+
+/// Fail with a fixed error message and a custom exit code
+///
+/// Same as [`fail`], but if this failure ends up as the reported error
+/// [`ParseFailure::exit_code`] returns `code` instead of the usual `1`, so
+/// [`run`](OptionParser::run) exits with this code. Useful for tools with documented exit codes
+/// where some particular problem must be reported with a specific number. Failures from other
+/// parsers keep using the usual exit code.
+///
+/// With [`run_inner`](OptionParser::run_inner) this failure is reported as a regular
+/// [`ParseFailure::Stderr`] carrying the custom code.
+///
+/// # Panics
+///
+/// Panics if `code` is `0`, failures must not exit with a success code
+///
+/// # Combinatoric usage
+/// ```rust
+/// # use bpaf::*;
+/// fn must_agree() -> impl Parser<()> {
+///     let a = long("accept").req_flag(());
+///     let no_a = fail_with_code("You must accept the license agreement with --accept", 3);
+///     construct!([a, no_a])
+/// }
+/// # let r = must_agree().to_options().run_inner(&[]).unwrap_err();
+/// # assert_eq!(r.exit_code(), 3);
+/// ```
+///
+/// # Example
+/// ```console
+/// $ app
+/// // exits with "You must accept the license agreement with --accept" and exit code 3
+/// $ app --accept
+/// // succeeds
+/// ```
+#[must_use]
+#[track_caller]
+pub fn fail_with_code<T>(msg: &'static str, code: u8) -> ParseFail<T> {
+    let code = std::num::NonZeroU8::new(code).expect("fail_with_code needs a non zero exit code");
+    ParseFail {
+        field1: msg,
+        field2: PhantomData,
+        code: Some(code),
     }
 }
 
+/// Fail and show the help message
+///
+/// This parser produces `T` of any type but instead of producing it when asked it fails, and
+/// the closest [`OptionParser`] containing it shows its help message, same as if user passed
+/// `--help`. Help is reported as [`ParseFailure::Stdout`] so the program exits with a success
+/// code. Can be useful when there's nothing sensible to do with the given command line and
+/// usage information is more helpful than an error message, for example when no subcommand was
+/// selected.
+///
+/// Similar to [`fail`] - other parsers take priority when used as one of the alternatives and
+/// it can be replaced with a value by [`fallback`](Parser::fallback) or [`optional`](Parser::optional).
+///
+/// [`fallback_to_usage`](OptionParser::fallback_to_usage) shows help only when no arguments are
+/// given at all and takes priority over `fail_with_help`, this parser shows the help when
+/// reached regardless of the command line, for example when some, but not all, required items
+/// are present.
+///
+/// # Combinatoric usage
+/// ```rust
+/// # use bpaf::*;
+/// #[derive(Debug, Clone)]
+/// enum Cmd {
+///     Build,
+///     Check,
+/// }
+///
+/// fn cmd() -> impl Parser<Cmd> {
+///     let build = pure(Cmd::Build).to_options().command("build");
+///     let check = pure(Cmd::Check).to_options().command("check");
+///     let nothing = fail_with_help();
+///     construct!([build, check, nothing])
+/// }
+/// # let r = cmd().to_options().run_inner(&[]).unwrap_err().unwrap_stdout();
+/// # assert!(r.starts_with("Usage: COMMAND ..."));
+/// ```
+///
+/// # Example
+/// ```console
+/// $ app
+/// // prints the help message and exits with a success code
+/// $ app build
+/// // succeeds
+/// ```
+#[must_use]
+pub fn fail_with_help<T>() -> ParseFailWithHelp<T> {
+    ParseFailWithHelp { ty: PhantomData }
+}
+
 /// Parse a [`flag`](NamedArg::flag)/[`switch`](NamedArg::switch)/[`argument`](NamedArg::argument) that has a short name
 ///
 /// You can chain multiple [`short`](NamedArg::short), [`long`](NamedArg::long) and
@@ -1396,12 +1786,109 @@ pub fn env(variable: &'static str) -> NamedArg {
 /// By default `bpaf` accepts positional items with or without `--` where values permit, you can
 /// further restrict the parser to accept positional items only on the right side of `--` using
 /// [`strict`](ParsePositional::strict).
+///
+/// # Several interpretations of the same value
+///
+/// To accept a value that can be parsed as one of several types - an IP address or a host name,
+/// a number or a name - combine a positional parser for every type with
+/// [`construct!`](crate::construct!) in a sum form. The value is consumed only once, by the
+/// interpretation that succeeds. When several interpretations succeed the one listed first
+/// wins, so put more specific types in front of more general ones: a `String` listed first
+/// would accept everything. When none of them succeed `bpaf` reports the error from the
+/// first one. Parsers with the same metavariable are shown as a single item in the usage
+/// line and in error messages.
+///
+/// ```rust
+/// # use bpaf::*;
+/// # use std::net::IpAddr;
+/// #[derive(Debug, Clone, PartialEq)]
+/// enum Target {
+///     Ip(IpAddr),
+///     Name(String),
+/// }
+///
+/// let ip = positional::<IpAddr>("TARGET").map(Target::Ip);
+/// let name = positional::<String>("TARGET").map(Target::Name);
+/// let parser = construct!([ip, name]).to_options().name("app");
+///
+/// let r = parser.run_inner(&["127.0.0.1"]).unwrap();
+/// assert_eq!(r, Target::Ip(IpAddr::from([127, 0, 0, 1])));
+///
+/// let r = parser.run_inner(&["localhost"]).unwrap();
+/// assert_eq!(r, Target::Name("localhost".to_owned()));
+///
+/// assert_eq!(parser.render_usage(), "Usage: app TARGET");
+/// ```
 #[cfg_attr(not(doctest), doc = include_str!("docs2/positional.md"))]
 #[must_use]
 pub fn positional<T>(metavar: &'static str) -> ParsePositional<T> {
     build_positional(metavar)
 }
 
+/// Parse a positional item described by [`ValueSpec`]
+///
+/// Works like [`positional`] with metavar, help, validation and completion taken from the spec,
+/// see [`ValueSpec`] for an example.
+#[must_use]
+pub fn positional_spec<T>(spec: ValueSpec<T>) -> impl Parser<T>
+where
+    T: FromStr + 'static,
+    <T as FromStr>::Err: std::fmt::Display,
+{
+    spec.positional()
+}
+
+/// Parse all the remaining positional items and convert them lazily
+///
+/// Works like [`positional`] followed by [`many`](Parser::many), but instead of a vector of
+/// converted values produces a [`PositionalStream`] - an iterator that converts items with
+/// [`FromStr`] one at a time as you consume it. Useful for tools that process thousands of file
+/// names and don't need all of them at once.
+///
+/// # Laziness guarantees
+///
+/// `bpaf` parses the command line eagerly: all the other parsers run and all the positional
+/// words for this parser are located before you get the iterator, so errors in other items,
+/// `--help` and shell completion are handled as usual. Words themselves are already in memory
+/// since they come from the command line, only the conversion to `T` is deferred and converted
+/// values are not retained by the iterator.
+///
+/// # Errors
+///
+/// Since conversion happens after `bpaf` is done parsing, it can't report bad values by itself.
+/// Iterator yields `Err` with a message like ``"couldn't parse `x`: invalid digit found in
+/// string"`` for the first item that fails to convert and stops after that: items after the bad
+/// one are never converted.
+///
+/// ```rust
+/// # use bpaf::*;
+/// let verbose = short('v').switch();
+/// let files = positional_stream::<u32>("N").help("Numbers to process");
+/// let parser = construct!(verbose, files).to_options();
+///
+/// let (verbose, files) = parser.run_inner(&["1", "-v", "2", "3"]).unwrap();
+/// assert!(verbose);
+/// let mut sum = 0;
+/// for n in files {
+///     sum += n.unwrap();
+/// }
+/// assert_eq!(sum, 6);
+///
+/// let (_, files) = parser.run_inner(&["1", "x", "3"]).unwrap();
+/// let r = files.collect::<Vec<_>>();
+/// assert_eq!(r, [Ok(1), Err("couldn't parse `x`: invalid digit found in string".to_owned())]);
+/// ```
+#[must_use]
+pub fn positional_stream<T>(metavar: &'static str) -> ParsePositionalStream<T> {
+    ParsePositionalStream {
+        inner: ParseMany {
+            inner: build_positional(metavar),
+            catch: false,
+        },
+        ty: PhantomData,
+    }
+}
+
 #[doc(hidden)]
 #[deprecated = "You should switch from command(name, sub) to sub.command(name)"]
 pub fn command<T>(name: &'static str, subparser: OptionParser<T>) -> ParseCommand<T>
@@ -1524,3 +2011,83 @@ pub fn choice<T: 'static>(parsers: impl IntoIterator<Item = Box<dyn Parser<T>>>)
     }
     this
 }
+
+/// Parse a group of independent optional items where at most one can be present
+///
+/// Takes a tuple of parsers and succeeds with a tuple of [`Option`]s, one for each parser in the
+/// same order: all of them are `None` if no items are present, otherwise exactly one is `Some`.
+/// Parsers can produce values of different types. If more than one item is present on the
+/// command line `at_most_one_of` fails with a message listing all the items in the group:
+/// ``only one of `--json`, `--format=FMT` may be given``.
+///
+/// Unlike parallel composition with [`construct!`]`([a, b])` that picks one of the alternatives
+/// and leaves the rest for other parsers this one consumes all the items and reports the
+/// conflict, and unlike an `enum` it doesn't require values to share a type.
+///
+/// An item counts as present when its parser produces a value. Parsers that succeed when
+/// their item is absent, such as a [`switch`](NamedArg::switch) or anything made
+/// [`optional`](Parser::optional) or given a [`fallback`](Parser::fallback), always produce
+/// one: such a member is never `None` and `at_most_one_of` fails as soon as any other item is
+/// given. Use [`req_flag`](NamedArg::req_flag) for flags and plain arguments instead.
+///
+/// Implemented for tuples of up to 6 parsers.
+///
+/// ```rust
+/// # use bpaf::*;
+/// fn output() -> impl Parser<(Option<bool>, Option<String>)> {
+///     let json = long("json").req_flag(true);
+///     let format = long("format").argument::<String>("FMT");
+///     at_most_one_of((json, format))
+/// }
+/// # let parser = output().to_options();
+/// # let res = parser.run_inner(&[]).unwrap();
+/// # assert_eq!(res, (None, None));
+/// # let res = parser.run_inner(&["--format", "csv"]).unwrap();
+/// # assert_eq!(res, (None, Some(String::from("csv"))));
+/// # let res = parser.run_inner(&["--json", "--format", "csv"]).unwrap_err().unwrap_stderr();
+/// # assert_eq!(res, "only one of `--json`, `--format=FMT` may be given");
+/// ```
+#[must_use]
+pub fn at_most_one_of<T, P>(parsers: P) -> impl Parser<T>
+where
+    P: AtMostOneOf<T>,
+{
+    parsers.at_most_one_of()
+}
+
+/// Check if `bpaf` is running parsers to generate shell completion
+///
+/// During a completion request `bpaf` runs all the parsers as usual so functions given to
+/// [`parse`](Parser::parse), [`fallback_with`](Parser::fallback_with), [`guard`](Parser::guard)
+/// and others are called as well. Use this function to skip expensive or side-effecting work
+/// such as network requests while the shell is only asking for completions.
+///
+/// The flag is set for the current thread while [`OptionParser::run`] or
+/// [`OptionParser::run_inner`] evaluate parsers for a completion request and is cleared once
+/// they return. It is always `false` outside of parsing and without the `autocomplete` feature.
+///
+/// ```rust
+/// # use bpaf::*;
+/// fn default_user() -> Result<String, String> {
+///     if is_completing() {
+///         return Ok(String::new());
+///     }
+///     // talk to the network or do some other slow work
+///     Ok("guest".to_owned())
+/// }
+///
+/// let user = long("user").argument::<String>("USER").fallback_with(default_user);
+/// let r = user.to_options().run_inner(&[]).unwrap();
+/// assert_eq!(r, "guest");
+/// ```
+#[must_use]
+pub fn is_completing() -> bool {
+    #[cfg(feature = "autocomplete")]
+    {
+        complete_run::is_completing()
+    }
+    #[cfg(not(feature = "autocomplete"))]
+    {
+        false
+    }
+}
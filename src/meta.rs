@@ -1,4 +1,5 @@
-use crate::{buffer::Doc, item::Item};
+use crate::{args::Arg, buffer::Doc, item::Item};
+use std::{borrow::Cow, rc::Rc};
 
 #[doc(hidden)]
 #[derive(Clone, Debug)]
@@ -20,7 +21,7 @@ pub enum Meta {
     /// Arguments form a subsection with buffer being it's header
     ///
     /// whole set of arguments go into the same section as the first one
-    Subsection(Box<Meta>, Box<Doc>),
+    Subsection(Box<Meta>, Header),
     /// Buffer is rendered after
     Suffix(Box<Meta>, Box<Doc>),
     /// This item is not rendered in the help message
@@ -29,6 +30,41 @@ pub enum Meta {
     CustomUsage(Box<Meta>, Box<Doc>),
     /// this meta must be prefixed with -- in unsage group
     Strict(Box<Meta>),
+    /// Items that can only get their values from environment variables, they are
+    /// not present in usage and listed in a separate section in the help message
+    Env(Box<Meta>),
+}
+
+/// Subsection header, lazy one is computed only when help is rendered
+#[doc(hidden)]
+#[derive(Clone)]
+pub enum Header {
+    Doc(Box<Doc>),
+    Lazy(Rc<dyn Fn() -> Doc>),
+}
+
+impl std::fmt::Debug for Header {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Header::Doc(doc) => doc.fmt(f),
+            Header::Lazy(_) => f.write_str("Lazy"),
+        }
+    }
+}
+
+impl Header {
+    pub(crate) fn doc(&self) -> Cow<'_, Doc> {
+        match self {
+            Header::Doc(doc) => Cow::Borrowed(doc),
+            Header::Lazy(f) => Cow::Owned(f()),
+        }
+    }
+}
+
+impl From<Doc> for Header {
+    fn from(value: Doc) -> Self {
+        Header::Doc(Box::new(value))
+    }
 }
 
 // to get std::mem::take to work
@@ -71,6 +107,18 @@ impl Meta {
         }
     }
 
+    /// Used by normalization function to collapse several interpretations of the same positional
+    /// item: `(HOST | HOST)` is rendered as `HOST`
+    fn positional_metavar(&self) -> Option<Cow<'static, str>> {
+        match self {
+            Meta::Item(i) => match i.as_ref() {
+                Item::Positional { metavar, .. } => Some(metavar.0.clone()),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+
     /// do a nested invariant check
     pub(crate) fn positional_invariant_check(&self, verbose: bool) {
         fn go(meta: &Meta, is_pos: &mut bool, v: bool) {
@@ -126,7 +174,7 @@ impl Meta {
                 | Meta::Subsection(m, _)
                 | Meta::Strict(m)
                 | Meta::Suffix(m, _) => go(m, is_pos, v),
-                Meta::Skip => {}
+                Meta::Skip | Meta::Env(_) => {}
             }
         }
         let mut is_pos = false;
@@ -136,6 +184,77 @@ impl Meta {
         go(self, &mut is_pos, verbose);
     }
 
+    /// Check that every adjacent block starts with a required item it can be anchored to
+    pub(crate) fn adjacent_invariant_check(&self) {
+        // Walks to the first item of an adjacent block, reports if it can be skipped, every
+        // branch of alternatives must have one. An optional flag such as a switch is fine:
+        // block is present when the flag is
+        fn first_required(meta: &Meta, optional: bool) -> Option<(&Item, bool)> {
+            match meta {
+                Meta::And(xs) => xs.first().and_then(|x| first_required(x, optional)),
+                Meta::Item(item) => Some((item, optional && !matches!(**item, Item::Flag { .. }))),
+                Meta::Or(xs) => {
+                    let mut res = None;
+                    for x in xs {
+                        match first_required(x, optional)? {
+                            (item, true) => return Some((item, true)),
+                            first => res = res.or(Some(first)),
+                        }
+                    }
+                    res
+                }
+                Meta::Skip | Meta::Env(_) => None,
+                Meta::Optional(x) => first_required(x, true),
+                Meta::Strict(x)
+                | Meta::Required(x)
+                | Meta::Adjacent(x)
+                | Meta::Many(x)
+                | Meta::Subsection(x, _)
+                | Meta::Suffix(x, _)
+                | Meta::CustomUsage(x, _) => first_required(x, optional),
+            }
+        }
+
+        match self {
+            Meta::And(xs) | Meta::Or(xs) => {
+                for x in xs {
+                    x.adjacent_invariant_check();
+                }
+            }
+            Meta::Item(i) => {
+                if let Item::Command { meta, .. } = &**i {
+                    meta.adjacent_invariant_check();
+                }
+            }
+            Meta::Adjacent(m) => {
+                match first_required(m, false) {
+                    None => panic!(
+                        "bpaf usage BUG: adjacent block must start with a visible named item, \
+                    positional or command but {:?} starts with a hidden parser or a pure value. \
+                    See bpaf documentation for `adjacent` for details.",
+                        m
+                    ),
+                    Some((i, true)) => panic!(
+                        "bpaf usage BUG: adjacent block is located by its first item, but {:?} \
+                    is optional. Make the first item required and the whole adjacent block \
+                    optional instead. See bpaf documentation for `adjacent` for details.",
+                        i
+                    ),
+                    Some((_, false)) => {}
+                }
+                m.adjacent_invariant_check();
+            }
+            Meta::Optional(m)
+            | Meta::Required(m)
+            | Meta::Many(m)
+            | Meta::CustomUsage(m, _)
+            | Meta::Subsection(m, _)
+            | Meta::Strict(m)
+            | Meta::Suffix(m, _) => m.adjacent_invariant_check(),
+            Meta::Skip | Meta::Env(_) => {}
+        }
+    }
+
     pub(crate) fn normalized(&self, for_usage: bool) -> Meta {
         let mut m = self.clone();
         let mut norm = StrictNorm::Pull;
@@ -153,12 +272,38 @@ impl Meta {
         m
     }
 
+    /// Collect items adjacent block can start with, one for each alternative
+    pub(crate) fn first_items<'a>(meta: &'a Meta, items: &mut Vec<&'a Item>) {
+        match meta {
+            Meta::And(xs) => {
+                if let Some(x) = xs.first() {
+                    Self::first_items(x, items);
+                }
+            }
+            Meta::Or(xs) => {
+                for x in xs {
+                    Self::first_items(x, items);
+                }
+            }
+            Meta::Item(item) => items.push(item),
+            Meta::Skip | Meta::Env(_) => {}
+            Meta::Optional(x)
+            | Meta::Strict(x)
+            | Meta::Required(x)
+            | Meta::Adjacent(x)
+            | Meta::Many(x)
+            | Meta::Subsection(x, _)
+            | Meta::Suffix(x, _)
+            | Meta::CustomUsage(x, _) => Self::first_items(x, items),
+        }
+    }
+
     /// Used by adjacent parsers since it inherits behavior of the front item
     pub(crate) fn first_item(meta: &Meta) -> Option<&Item> {
         match meta {
             Meta::And(xs) => xs.first().and_then(Self::first_item),
             Meta::Item(item) => Some(item),
-            Meta::Skip | Meta::Or(_) => None,
+            Meta::Skip | Meta::Or(_) | Meta::Env(_) => None,
             Meta::Optional(x)
             | Meta::Strict(x)
             | Meta::Required(x)
@@ -218,12 +363,21 @@ impl Meta {
                     *self = replacement;
                 } else {
                     let mut saw_cmd = false;
+                    let mut saw_pos = Vec::new();
                     // drop all the commands apart from the first one
+                    // and positionals with the same metavar as one of the previous ones
                     xs.retain(|m| {
                         let is_cmd = m.is_command();
                         let keep = !(is_cmd && saw_cmd);
                         saw_cmd |= is_cmd;
-                        keep
+                        match m.positional_metavar() {
+                            Some(pos) if saw_pos.contains(&pos) => false,
+                            Some(pos) => {
+                                saw_pos.push(pos);
+                                keep
+                            }
+                            None => keep,
+                        }
                     });
                     match xs.len() {
                         0 => *self = Meta::Skip,
@@ -274,6 +428,10 @@ impl Meta {
             Meta::Skip => {
                 // nothing to do with items and skip just bubbles upwards
             }
+            Meta::Env(_) => {
+                // environment variables are not a part of usage
+                *self = Meta::Skip;
+            }
             Meta::CustomUsage(m, u) => {
                 m.normalize(for_usage, norm);
                 // strip CustomUsage if we are not in usage so writer can simply render it
@@ -320,6 +478,81 @@ impl Meta {
         }
     }
 
+    /// Collect names of commands available at this level, nested commands are not included
+    pub(crate) fn collect_commands(&self, names: &mut Vec<String>) {
+        match self {
+            Meta::And(xs) | Meta::Or(xs) => {
+                for x in xs {
+                    x.collect_commands(names);
+                }
+            }
+            Meta::Item(m) => {
+                if let Item::Command { name, short, .. } = &**m {
+                    names.push((*name).to_owned());
+                    names.extend(short.map(|s| s.to_string()));
+                }
+            }
+            Meta::CustomUsage(m, _)
+            | Meta::Required(m)
+            | Meta::Optional(m)
+            | Meta::Adjacent(m)
+            | Meta::Subsection(m, _)
+            | Meta::Suffix(m, _)
+            | Meta::Many(m)
+            | Meta::Strict(m) => m.collect_commands(names),
+            Meta::Skip | Meta::Env(_) => {}
+        }
+    }
+
+    /// Find a command available at this level by its name or short name
+    pub(crate) fn find_command(&self, name: &str) -> Option<&Item> {
+        match self {
+            Meta::And(xs) | Meta::Or(xs) => xs.iter().find_map(|x| x.find_command(name)),
+            Meta::Item(m) => match &**m {
+                Item::Command { name: n, short, .. }
+                    if *n == name || short.map_or(false, |s| name == s.to_string()) =>
+                {
+                    Some(m)
+                }
+                _ => None,
+            },
+            Meta::CustomUsage(m, _)
+            | Meta::Required(m)
+            | Meta::Optional(m)
+            | Meta::Adjacent(m)
+            | Meta::Subsection(m, _)
+            | Meta::Suffix(m, _)
+            | Meta::Many(m)
+            | Meta::Strict(m) => m.find_command(name),
+            Meta::Skip | Meta::Env(_) => None,
+        }
+    }
+
+    /// Find a named item available at this level that accepts `arg` as one of its names,
+    /// hidden aliases included
+    pub(crate) fn find_named(&self, arg: &Arg) -> Option<&Item> {
+        match self {
+            Meta::And(xs) | Meta::Or(xs) => xs.iter().find_map(|x| x.find_named(arg)),
+            Meta::Item(m) => match &**m {
+                Item::Flag { shorts, longs, .. } | Item::Argument { shorts, longs, .. }
+                    if shorts.iter().any(|s| arg.match_short(*s))
+                        || longs.iter().any(|l| arg.match_long(l)) =>
+                {
+                    Some(m)
+                }
+                _ => None,
+            },
+            Meta::CustomUsage(m, _)
+            | Meta::Required(m)
+            | Meta::Optional(m)
+            | Meta::Adjacent(m)
+            | Meta::Subsection(m, _)
+            | Meta::Suffix(m, _)
+            | Meta::Many(m) => m.find_named(arg),
+            Meta::Skip | Meta::Strict(_) | Meta::Env(_) => None,
+        }
+    }
+
     /// collect different kinds of short names for disambiguation
     pub(crate) fn collect_shorts(&self, flags: &mut Vec<char>, args: &mut Vec<char>) {
         match self {
@@ -345,7 +578,7 @@ impl Meta {
             | Meta::Many(m) => {
                 m.collect_shorts(flags, args);
             }
-            Meta::Skip | Meta::Strict(_) => {}
+            Meta::Skip | Meta::Strict(_) | Meta::Env(_) => {}
         }
     }
 }
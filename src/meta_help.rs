@@ -1,24 +1,25 @@
-use std::collections::BTreeSet;
+use std::{borrow::Cow, collections::BTreeSet};
 
 use crate::{
     buffer::{Block, Doc, Style, Token},
     info::Info,
     item::{Item, ShortLong},
+    params::HelpSep,
     Meta,
 };
 
 #[doc(hidden)]
-#[derive(Debug, Clone, Copy)]
-pub struct Metavar(pub(crate) &'static str);
+#[derive(Debug, Clone)]
+pub struct Metavar(pub(crate) Cow<'static, str>);
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone)]
 pub(crate) enum HelpItem<'a> {
     DecorSuffix {
         help: &'a Doc,
         ty: HiTy,
     },
     GroupStart {
-        help: &'a Doc,
+        help: Cow<'a, Doc>,
         ty: HiTy,
     },
     GroupEnd {
@@ -30,7 +31,7 @@ pub(crate) enum HelpItem<'a> {
         help: Option<&'a Doc>,
     },
     Positional {
-        metavar: Metavar,
+        metavar: &'a Metavar,
         help: Option<&'a Doc>,
     },
     Command {
@@ -45,12 +46,15 @@ pub(crate) enum HelpItem<'a> {
         name: ShortLong,
         env: Option<&'static str>,
         help: Option<&'a Doc>,
+        required: bool,
     },
     Argument {
         name: ShortLong,
-        metavar: Metavar,
+        metavar: &'a Metavar,
         env: Option<&'static str>,
         help: Option<&'a Doc>,
+        sep: HelpSep,
+        required: bool,
     },
     AnywhereStart {
         inner: &'a Meta,
@@ -59,6 +63,11 @@ pub(crate) enum HelpItem<'a> {
     AnywhereStop {
         ty: HiTy,
     },
+    Env {
+        name: &'static str,
+        metavar: Option<&'a Metavar>,
+        help: Option<&'a Doc>,
+    },
 }
 impl HelpItem<'_> {
     fn has_help(&self) -> bool {
@@ -67,7 +76,8 @@ impl HelpItem<'_> {
             | HelpItem::Command { help, .. }
             | HelpItem::Flag { help, .. }
             | HelpItem::Any { help, .. }
-            | HelpItem::Argument { help, .. } => help.is_some(),
+            | HelpItem::Argument { help, .. }
+            | HelpItem::Env { help, .. } => help.is_some(),
             HelpItem::GroupStart { .. } | HelpItem::DecorSuffix { .. } => true,
             HelpItem::GroupEnd { .. }
             | HelpItem::AnywhereStart { .. }
@@ -90,6 +100,7 @@ impl HelpItem<'_> {
             HelpItem::Any { anywhere: true, .. }
             | HelpItem::Flag { .. }
             | HelpItem::Argument { .. } => HiTy::Flag,
+            HelpItem::Env { .. } => HiTy::Env,
         }
     }
 }
@@ -107,6 +118,7 @@ pub(crate) enum HiTy {
     Flag,
     Command,
     Positional,
+    Env,
 }
 
 enum ItemBlock {
@@ -148,7 +160,8 @@ impl<'a, 'b> Iterator for HelpItemsIter<'a, 'b> {
                 | HelpItem::Command { .. }
                 | HelpItem::Positional { .. }
                 | HelpItem::Flag { .. }
-                | HelpItem::Argument { .. } => {
+                | HelpItem::Argument { .. }
+                | HelpItem::Env { .. } => {
                     let ty = item.ty();
                     match self.block {
                         ItemBlock::No => ty == self.target,
@@ -189,6 +202,7 @@ impl Meta {
             | Meta::Strict(x)
             | Meta::CustomUsage(x, _) => x.peek_front_ty(),
             Meta::Item(i) => Some(HiTy::from(i.as_ref())),
+            Meta::Env(_) => Some(HiTy::Env),
             Meta::Skip => None,
         }
     }
@@ -196,12 +210,21 @@ impl Meta {
 
 impl<'a> HelpItems<'a> {
     /// Recursively classify contents of the Meta
+    ///
+    /// Named items that must be present - not inside of `optional`, `fallback`, `many` or one
+    /// of several alternatives are marked as required, `some` keeps them required
     pub(crate) fn append_meta(&mut self, meta: &'a Meta) {
-        fn go<'a>(hi: &mut HelpItems<'a>, meta: &'a Meta, no_ss: bool) {
+        fn go<'a>(hi: &mut HelpItems<'a>, meta: &'a Meta, no_ss: bool, req: bool) {
             match meta {
-                Meta::And(xs) | Meta::Or(xs) => {
+                Meta::And(xs) => {
                     for x in xs {
-                        go(hi, x, no_ss);
+                        go(hi, x, no_ss, req);
+                    }
+                }
+                Meta::Or(xs) => {
+                    let req = req && xs.len() == 1;
+                    for x in xs {
+                        go(hi, x, no_ss, req);
                     }
                 }
                 Meta::Adjacent(m) => {
@@ -210,42 +233,95 @@ impl<'a> HelpItems<'a> {
                             inner: m.as_ref(),
                             ty,
                         });
-                        go(hi, m, no_ss);
+                        go(hi, m, no_ss, req);
                         hi.items.push(HelpItem::AnywhereStop { ty });
                     }
                 }
-                Meta::CustomUsage(x, _)
-                | Meta::Required(x)
-                | Meta::Optional(x)
-                | Meta::Many(x)
-                | Meta::Strict(x) => go(hi, x, no_ss),
+                Meta::CustomUsage(x, _) | Meta::Required(x) | Meta::Many(x) | Meta::Strict(x) => {
+                    go(hi, x, no_ss, req);
+                }
+                Meta::Optional(x) => go(hi, x, no_ss, false),
                 Meta::Item(item) => {
                     if matches!(item.as_ref(), Item::Positional { help: None, .. }) {
                         return;
                     }
-                    hi.items.push(HelpItem::from(item.as_ref()));
+                    let mut help_item = HelpItem::from(item.as_ref());
+                    if let HelpItem::Flag { required, .. } | HelpItem::Argument { required, .. } =
+                        &mut help_item
+                    {
+                        *required = req;
+                    }
+                    hi.items.push(help_item);
                 }
                 Meta::Subsection(m, help) => {
                     if let Some(ty) = m.peek_front_ty() {
                         if no_ss {
-                            go(hi, m, true);
+                            go(hi, m, true, req);
                         } else {
-                            hi.items.push(HelpItem::GroupStart { help, ty });
-                            go(hi, m, true);
+                            hi.items.push(HelpItem::GroupStart {
+                                help: help.doc(),
+                                ty,
+                            });
+                            go(hi, m, true, req);
                             hi.items.push(HelpItem::GroupEnd { ty });
                         }
                     }
                 }
                 Meta::Suffix(m, help) => {
                     if let Some(ty) = m.peek_front_ty() {
-                        go(hi, m, no_ss);
+                        go(hi, m, no_ss, req);
                         hi.items.push(HelpItem::DecorSuffix { help, ty });
                     }
                 }
+                Meta::Env(m) => go_env(hi, m),
                 Meta::Skip => (),
             }
         }
-        go(self, meta, false);
+
+        // only environment variable names are of interest here, usual names are not available
+        fn go_env<'a>(hi: &mut HelpItems<'a>, meta: &'a Meta) {
+            match meta {
+                Meta::And(xs) | Meta::Or(xs) => {
+                    for x in xs {
+                        go_env(hi, x);
+                    }
+                }
+                Meta::Optional(x)
+                | Meta::Required(x)
+                | Meta::Adjacent(x)
+                | Meta::Many(x)
+                | Meta::Subsection(x, _)
+                | Meta::Suffix(x, _)
+                | Meta::Strict(x)
+                | Meta::CustomUsage(x, _)
+                | Meta::Env(x) => go_env(hi, x),
+                Meta::Item(item) => match item.as_ref() {
+                    Item::Flag {
+                        env: Some(name),
+                        help,
+                        ..
+                    } => hi.items.push(HelpItem::Env {
+                        name,
+                        metavar: None,
+                        help: help.as_ref(),
+                    }),
+                    Item::Argument {
+                        env: Some(name),
+                        metavar,
+                        help,
+                        ..
+                    } => hi.items.push(HelpItem::Env {
+                        name,
+                        metavar: Some(metavar),
+                        help: help.as_ref(),
+                    }),
+                    _ => {}
+                },
+                Meta::Skip => {}
+            }
+        }
+
+        go(self, meta, false, true);
     }
 
     fn find_group(&self) -> Option<std::ops::RangeInclusive<usize>> {
@@ -281,7 +357,7 @@ impl<'a> From<&'a Item> for HelpItem<'a> {
     fn from(item: &'a Item) -> Self {
         match item {
             Item::Positional { metavar, help } => Self::Positional {
-                metavar: *metavar,
+                metavar,
                 help: help.as_ref(),
             },
             Item::Command {
@@ -306,10 +382,12 @@ impl<'a> From<&'a Item> for HelpItem<'a> {
                 env,
                 help,
                 shorts: _,
+                longs: _,
             } => Self::Flag {
                 name: *name,
                 env: *env,
                 help: help.as_ref(),
+                required: false,
             },
             Item::Argument {
                 name,
@@ -317,11 +395,15 @@ impl<'a> From<&'a Item> for HelpItem<'a> {
                 env,
                 help,
                 shorts: _,
+                longs: _,
+                sep,
             } => Self::Argument {
                 name: *name,
-                metavar: *metavar,
+                metavar,
                 env: *env,
                 help: help.as_ref(),
+                sep: *sep,
+                required: false,
             },
             Item::Any {
                 metavar,
@@ -338,16 +420,16 @@ impl<'a> From<&'a Item> for HelpItem<'a> {
 
 impl Doc {
     #[inline(never)]
-    pub(crate) fn metavar(&mut self, metavar: Metavar) {
+    pub(crate) fn metavar(&mut self, metavar: &Metavar) {
         if metavar
             .0
             .chars()
             .all(|c| c.is_uppercase() || c.is_ascii_digit() || c == '-' || c == '_')
         {
-            self.write_str(metavar.0, Style::Metavar);
+            self.write_str(&metavar.0, Style::Metavar);
         } else {
             self.write_char('<', Style::Metavar);
-            self.write_str(metavar.0, Style::Metavar);
+            self.write_str(&metavar.0, Style::Metavar);
             self.write_char('>', Style::Metavar);
         }
     }
@@ -390,7 +472,7 @@ fn write_help_item(buf: &mut Doc, item: &HelpItem, include_env: bool) {
         }
         HelpItem::Positional { metavar, help } => {
             buf.token(Token::BlockStart(Block::ItemTerm));
-            buf.metavar(*metavar);
+            buf.metavar(metavar);
             buf.token(Token::BlockEnd(Block::ItemTerm));
             if let Some(help) = help {
                 buf.token(Token::BlockStart(Block::ItemBody));
@@ -419,7 +501,9 @@ fn write_help_item(buf: &mut Doc, item: &HelpItem, include_env: bool) {
                 buf.token(Token::BlockEnd(Block::ItemBody));
             }
         }
-        HelpItem::Flag { name, env, help } => {
+        HelpItem::Flag {
+            name, env, help, ..
+        } => {
             buf.token(Token::BlockStart(Block::ItemTerm));
             write_shortlong(buf, *name);
             buf.token(Token::BlockEnd(Block::ItemTerm));
@@ -453,11 +537,14 @@ fn write_help_item(buf: &mut Doc, item: &HelpItem, include_env: bool) {
             metavar,
             env,
             help,
+            sep,
+            required: _,
         } => {
             buf.token(Token::BlockStart(Block::ItemTerm));
             write_shortlong(buf, *name);
-            buf.write_str("=", Style::Text);
-            buf.metavar(*metavar);
+            let long = !matches!(name, ShortLong::Short(_));
+            buf.write_str(sep.as_str(long), Style::Text);
+            buf.metavar(metavar);
             buf.token(Token::BlockEnd(Block::ItemTerm));
 
             if let Some(help) = help {
@@ -497,6 +584,24 @@ fn write_help_item(buf: &mut Doc, item: &HelpItem, include_env: bool) {
             buf.token(Token::BlockStart(Block::Block));
             buf.token(Token::BlockEnd(Block::Block));
         }
+        HelpItem::Env {
+            name,
+            metavar,
+            help,
+        } => {
+            buf.token(Token::BlockStart(Block::ItemTerm));
+            buf.write_str(name, Style::Literal);
+            if let Some(metavar) = metavar {
+                buf.write_char('=', Style::Text);
+                buf.metavar(metavar);
+            }
+            buf.token(Token::BlockEnd(Block::ItemTerm));
+            if let Some(help) = help {
+                buf.token(Token::BlockStart(Block::ItemBody));
+                buf.doc(help);
+                buf.token(Token::BlockEnd(Block::ItemBody));
+            }
+        }
     }
 }
 
@@ -538,16 +643,7 @@ pub(crate) fn render_help(
     }
 
     buf.token(Token::BlockStart(Block::Block));
-    if let Some(usage) = &info.usage {
-        buf.doc(usage);
-    } else {
-        buf.write_str("Usage", Style::Emphasis);
-        buf.write_str(": ", Style::Text);
-        buf.token(Token::BlockStart(Block::Mono));
-        buf.write_path(path);
-        buf.write_meta(parser_meta, true);
-        buf.token(Token::BlockEnd(Block::Mono));
-    }
+    buf.write_usage(path, info, parser_meta);
     buf.token(Token::BlockEnd(Block::Block));
 
     if let Some(t) = &info.header {
@@ -556,11 +652,21 @@ pub(crate) fn render_help(
         buf.token(Token::BlockEnd(Block::Block));
     }
 
+    // help and version flags are always accepted but never required
+    let optional_help_meta;
+    let help_meta = if info.split_required {
+        optional_help_meta = Meta::Optional(Box::new(help_meta.clone()));
+        &optional_help_meta
+    } else {
+        help_meta
+    };
+
     let mut items = HelpItems::default();
     items.append_meta(parser_meta);
     items.append_meta(help_meta);
 
-    buf.write_help_item_groups(items, include_env);
+    buf.write_help_item_groups(items, include_env, info.split_required);
+    buf.write_examples(info);
 
     if let Some(footer) = &info.footer {
         buf.token(Token::BlockStart(Block::Block));
@@ -579,7 +685,8 @@ struct Dedup {
 impl Dedup {
     fn check(&mut self, item: &HelpItem) -> bool {
         match item {
-            HelpItem::DecorSuffix { .. } => std::mem::take(&mut self.keep),
+            // an item can have several suffixes: possible values, default value, etc.
+            HelpItem::DecorSuffix { .. } => self.keep,
             HelpItem::GroupStart { .. }
             | HelpItem::GroupEnd { .. }
             | HelpItem::AnywhereStart { .. }
@@ -614,13 +721,22 @@ impl Dedup {
                     .insert(format!("{:?} {} {:?}", name, metavar.0, help));
                 self.keep
             }
+            HelpItem::Env { name, help, .. } => {
+                self.keep = self.items.insert(format!("{:?} {:?}", name, help));
+                self.keep
+            }
         }
     }
 }
 
 impl Doc {
     #[inline(never)]
-    pub(crate) fn write_help_item_groups(&mut self, mut items: HelpItems, include_env: bool) {
+    pub(crate) fn write_help_item_groups(
+        &mut self,
+        mut items: HelpItems,
+        include_env: bool,
+        split_required: bool,
+    ) {
         while let Some(range) = items.find_group() {
             let mut dd = Dedup::default();
             for item in items.items.drain(range) {
@@ -630,18 +746,79 @@ impl Doc {
             }
         }
 
-        for (ty, name) in [
-            (HiTy::Positional, "Available positional items:"),
-            (HiTy::Flag, "Available options:"),
-            (HiTy::Command, "Available commands:"),
-        ] {
-            self.write_help_items(&items, ty, name, include_env);
+        let required = if split_required { Some(false) } else { None };
+        self.write_help_items(
+            &items,
+            HiTy::Positional,
+            "Available positional items:",
+            include_env,
+            None,
+        );
+        if split_required {
+            self.write_help_items(
+                &items,
+                HiTy::Flag,
+                "Required options:",
+                include_env,
+                Some(true),
+            );
         }
+        self.write_help_items(
+            &items,
+            HiTy::Flag,
+            "Available options:",
+            include_env,
+            required,
+        );
+        self.write_help_items(
+            &items,
+            HiTy::Command,
+            "Available commands:",
+            include_env,
+            None,
+        );
+        self.write_help_items(
+            &items,
+            HiTy::Env,
+            "Environment variables:",
+            include_env,
+            None,
+        );
     }
 
+    /// Write a section with items of a given type
+    ///
+    /// With `required` set only named items that are required or not required are written,
+    /// suffixes follow their items, adjacent blocks are never required
     #[inline(never)]
-    fn write_help_items(&mut self, items: &HelpItems, ty: HiTy, name: &str, include_env: bool) {
-        let mut xs = items.items_of_ty(ty).peekable();
+    fn write_help_items(
+        &mut self,
+        items: &HelpItems,
+        ty: HiTy,
+        name: &str,
+        include_env: bool,
+        required: Option<bool>,
+    ) {
+        let mut item_required = false;
+        let mut anywhere = false;
+        let mut xs = items
+            .items_of_ty(ty)
+            .filter(|item| {
+                match item {
+                    HelpItem::Flag { required, .. } | HelpItem::Argument { required, .. } => {
+                        item_required = *required && !anywhere;
+                    }
+                    HelpItem::AnywhereStart { .. } => {
+                        anywhere = true;
+                        item_required = false;
+                    }
+                    HelpItem::AnywhereStop { .. } => anywhere = false,
+                    HelpItem::DecorSuffix { .. } => {}
+                    _ => item_required = false,
+                }
+                required.map_or(true, |r| r == item_required)
+            })
+            .peekable();
         if xs.peek().is_some() {
             self.token(Token::BlockStart(Block::Block));
             self.token(Token::BlockStart(Block::Section2));
@@ -659,6 +836,76 @@ impl Doc {
         }
     }
 
+    /// Write usage examples added with `OptionParser::example`, if any
+    pub(crate) fn write_examples(&mut self, info: &Info) {
+        if info.examples.is_empty() {
+            return;
+        }
+        self.token(Token::BlockStart(Block::Block));
+        self.token(Token::BlockStart(Block::Section2));
+        self.write_str("Examples:", Style::Emphasis);
+        self.token(Token::BlockEnd(Block::Section2));
+        self.token(Token::BlockStart(Block::DefinitionList));
+        for (invocation, descr) in &info.examples {
+            self.token(Token::BlockStart(Block::ItemTerm));
+            self.write_str(invocation, Style::Literal);
+            self.token(Token::BlockEnd(Block::ItemTerm));
+            self.token(Token::BlockStart(Block::ItemBody));
+            self.doc(descr);
+            self.token(Token::BlockEnd(Block::ItemBody));
+        }
+        self.token(Token::BlockEnd(Block::DefinitionList));
+        self.token(Token::BlockEnd(Block::Block));
+    }
+
+    /// Write a list of commands available in `meta` with the first line of their help under a
+    /// custom title
+    pub(crate) fn write_command_summary(&mut self, meta: &Meta, title: &str) {
+        let mut items = HelpItems::default();
+        items.append_meta(meta);
+        let mut xs = items.items_of_ty(HiTy::Command).peekable();
+        if xs.peek().is_none() {
+            return;
+        }
+        self.token(Token::BlockStart(Block::Block));
+        self.token(Token::BlockStart(Block::Section2));
+        self.write_str(title, Style::Emphasis);
+        self.token(Token::BlockEnd(Block::Section2));
+        self.token(Token::BlockStart(Block::DefinitionList));
+        let mut dd = Dedup::default();
+        for item in xs {
+            if let HelpItem::Command { name, help, .. } = item {
+                if !dd.check(item) {
+                    continue;
+                }
+                self.token(Token::BlockStart(Block::ItemTerm));
+                self.write_str(name, Style::Literal);
+                self.token(Token::BlockEnd(Block::ItemTerm));
+                if let Some(help) = help.and_then(Doc::first_line) {
+                    self.token(Token::BlockStart(Block::ItemBody));
+                    self.doc(&help);
+                    self.token(Token::BlockEnd(Block::ItemBody));
+                }
+            }
+        }
+        self.token(Token::BlockEnd(Block::DefinitionList));
+        self.token(Token::BlockEnd(Block::Block));
+    }
+
+    /// Write either custom or automatically derived usage line
+    pub(crate) fn write_usage(&mut self, path: &[String], info: &Info, parser_meta: &Meta) {
+        if let Some(usage) = &info.usage {
+            self.doc(usage);
+        } else {
+            self.write_str("Usage", Style::Emphasis);
+            self.write_str(": ", Style::Text);
+            self.token(Token::BlockStart(Block::Mono));
+            self.write_path(path);
+            self.write_meta(parser_meta, true);
+            self.token(Token::BlockEnd(Block::Mono));
+        }
+    }
+
     pub(crate) fn write_path(&mut self, path: &[String]) {
         for item in path {
             self.write_str(item, Style::Literal);
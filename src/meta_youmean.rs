@@ -33,6 +33,10 @@ pub(crate) fn suggest(args: &State, meta: &Meta) -> Option<(usize, Suggestion)>
     if matches!(arg, crate::args::Arg::PosWord(_)) {
         return None;
     }
+    // a valid name or a hidden alias used in a wrong place is not a typo
+    if meta.find_named(arg).is_some() {
+        return None;
+    }
     // it also should be a printable name
     let actual = arg.to_string();
 
@@ -86,6 +90,7 @@ pub(crate) fn suggest(args: &State, meta: &Meta) -> Option<(usize, Suggestion)>
                         | HelpItem::Positional { .. }
                         | HelpItem::AnywhereStart { .. }
                         | HelpItem::AnywhereStop { .. }
+                        | HelpItem::Env { .. }
                         | HelpItem::Any { .. } => {}
                     }
                 }
@@ -110,6 +115,7 @@ pub(crate) fn suggest(args: &State, meta: &Meta) -> Option<(usize, Suggestion)>
             | HelpItem::GroupEnd { .. }
             | HelpItem::AnywhereStart { .. }
             | HelpItem::AnywhereStop { .. }
+            | HelpItem::Env { .. }
             | HelpItem::Any { .. } => {}
         }
     }
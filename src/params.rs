@@ -33,6 +33,13 @@
 //! literal can't start with `-` unless separated from the flag with `=`. For short flags value
 //! can follow immediately: `-fbar`.
 //!
+//! Similar to `getopt` short flags can be combined with a short argument in a single block as long
+//! as the argument comes last: with switches `-x` and `-v` and an argument `-f`, command lines
+//! `-xvf file`, `-xvffile` and `-xvf=file` all parse the same way. Everything past the argument
+//! name is its value, so `-xfv file` gives `v` to `-f` and leaves `file` unconsumed. A block with
+//! an unknown name in front of the argument is not split and is treated as a single item, a block
+//! with a name used by both a flag and an argument is rejected as ambiguous.
+//!
 #![cfg_attr(not(doctest), doc = include_str!("docs2/argument.md"))]
 //!
 //! ## Positional
@@ -59,7 +66,9 @@
 //!
 #![cfg_attr(not(doctest), doc = include_str!("docs2/command.md"))]
 //!
-use std::{ffi::OsString, marker::PhantomData, str::FromStr};
+use std::{
+    ffi::OsString, marker::PhantomData, ops::RangeInclusive, path::PathBuf, rc::Rc, str::FromStr,
+};
 
 use crate::{
     args::{Arg, State},
@@ -67,7 +76,8 @@ use crate::{
     from_os_str::parse_os_str,
     item::ShortLong,
     meta_help::Metavar,
-    Doc, Error, Item, Meta, OptionParser, Parser,
+    structs::ParseMany,
+    Doc, Error, Item, Meta, OptionParser, ParseFailure, Parser,
 };
 
 #[cfg(doc)]
@@ -98,10 +108,12 @@ use crate::{any, command, env, long, positional, short};
 /// 3. If `short` or `long` annotation is present with an argument - those are values `bpaf` would
 ///    use instead of the original field name
 /// 4. You can specify many `short` and `long` names, any past the first one of each type will
-///    become hidden aliases
+///    become hidden aliases. Kinds can be mixed in any order, `short('a'), long("alpha"), short('b')`
+///    shows `-a` and `--alpha` in `--help` and accepts `-b` as well.
 /// 5. If `env(arg)` annotation is present - in addition to long/short names derived according to
 ///    rules 1..3 `bpaf` would also parse environment variable `arg` which can be a string literal
-///    or an expression.
+///    or an expression. With several `env` annotations the first one is shown in `--help`, the
+///    rest are hidden aliases.
 #[cfg_attr(not(doctest), doc = include_str!("docs2/named_arg_derive.md"))]
 #[derive(Clone, Debug)]
 pub struct NamedArg {
@@ -118,6 +130,7 @@ impl NamedArg {
             help: self.help.clone(),
             env: self.env.first().copied(),
             shorts: self.short.clone(),
+            longs: self.long.clone(),
         })
     }
 }
@@ -254,6 +267,261 @@ impl NamedArg {
         build_argument(self, metavar)
     }
 
+    /// Argument restricted to an inclusive numeric range
+    ///
+    /// Parses the value with [`FromStr`] and checks that it belongs to `range`. Values outside of
+    /// the range are rejected with an error like `LEVEL must be in 2..=16`, where `LEVEL` is the
+    /// `metavar`. Help and usage show the range in place of the metavar: `--level=2-16`, ranges
+    /// with negative bounds are shown as `--offset=<-5..=-1>`.
+    ///
+    /// ```rust
+    /// # use bpaf::*;
+    /// let level = long("level")
+    ///     .help("Compression level")
+    ///     .argument_range::<u8>("LEVEL", 2..=16);
+    /// let parser = level.to_options();
+    ///
+    /// let r = parser.run_inner(&["--level", "9"]).unwrap();
+    /// assert_eq!(r, 9);
+    ///
+    /// let r = parser.run_inner(&["--level", "20"]).unwrap_err().unwrap_stderr();
+    /// assert_eq!(r, "couldn't parse `20`: LEVEL must be in 2..=16");
+    ///
+    /// let r = parser.run_inner(&["--help"]).unwrap_err().unwrap_stdout();
+    /// assert!(r.contains("--level=2-16  Compression level"));
+    /// ```
+    #[must_use]
+    pub fn argument_range<T>(
+        self,
+        metavar: &'static str,
+        range: RangeInclusive<T>,
+    ) -> impl Parser<T>
+    where
+        T: FromStr + PartialOrd + std::fmt::Display + 'static,
+        <T as FromStr>::Err: std::fmt::Display,
+    {
+        let start = range.start().to_string();
+        let end = range.end().to_string();
+        let bounds = format!("{}..={}", start, end);
+        let mut parser = build_argument::<T>(self, metavar);
+        // `-5--1` is hard to read, use the range syntax instead
+        parser.shown = Some(if start.starts_with('-') || end.starts_with('-') {
+            bounds.clone()
+        } else {
+            format!("{}-{}", start, end)
+        });
+        parser.parse(move |value| {
+            if range.contains(&value) {
+                Ok(value)
+            } else {
+                Err(format!("{} must be in {}", metavar, bounds))
+            }
+        })
+    }
+
+    /// Argument with a value described by [`ValueSpec`]
+    ///
+    /// Works like [`argument`](NamedArg::argument) with metavar, validation and completion taken
+    /// from the spec. Help from the spec is used if this name doesn't have its own.
+    ///
+    /// ```rust
+    /// # use bpaf::*;
+    /// let level = ValueSpec::<u8>::new("LEVEL")
+    ///     .help("Compression level")
+    ///     .guard(|l| *l <= 9, "level must be between 0 and 9");
+    /// let parser = short('l').value(level).to_options();
+    ///
+    /// let r = parser.run_inner(&["-l", "5"]).unwrap();
+    /// assert_eq!(r, 5);
+    /// ```
+    #[must_use]
+    pub fn value<T>(mut self, spec: ValueSpec<T>) -> impl Parser<T>
+    where
+        T: FromStr + 'static,
+        <T as FromStr>::Err: std::fmt::Display,
+    {
+        if self.help.is_none() {
+            self.help = spec.help.clone();
+        }
+        let parser = build_argument::<T>(self, spec.metavar);
+        spec.apply(parser)
+    }
+
+    /// Argument with a value that is split into words and parsed by a nested parser
+    ///
+    /// Takes a single value such as `--run "build --release"`, splits it into words and runs
+    /// `inner` on them as if they were passed on a separate command line, so a tool can accept
+    /// a small embedded command line in a single argument. Help requested in the value
+    /// (`--run=--help`) prints help for `inner`, parse errors from `inner` are reported with the
+    /// flag name in front: ``--run: `--bad` is not expected in this context``, or with the name
+    /// of the environment variable if the value comes from [`env`](NamedArg::env).
+    ///
+    /// The value is split with simplified shell rules:
+    /// - words are separated by one or more whitespace characters
+    /// - text in single quotes is taken as is: `'a b\c'` gives `a b\c`
+    /// - text in double quotes can contain escaped `\"` and `\\`, other backslashes are kept:
+    ///   `"a \"b\" \n"` gives `a "b" \n`
+    /// - outside of quotes a backslash makes the next character literal: `a\ b` gives `a b`
+    /// - quoted and unquoted parts next to each other form one word, `''` gives an empty word
+    /// - there's no variable expansion, globbing or comments
+    ///
+    /// A value with an unterminated quote fails to parse. Same as with any other
+    /// [`argument`](NamedArg::argument) a value that starts with `-` must be attached to the
+    /// flag with `=`: `--run="--release app"`.
+    ///
+    /// ```rust
+    /// # use bpaf::*;
+    /// let release = long("release").switch();
+    /// let target = positional::<String>("TARGET");
+    /// let build = construct!(release, target).to_options();
+    ///
+    /// let parser = long("run")
+    ///     .help("Build command to run")
+    ///     .argument_parsed_by("CMD", build)
+    ///     .to_options();
+    ///
+    /// let r = parser.run_inner(&["--run=--release 'my app'"]).unwrap();
+    /// assert_eq!(r, (true, "my app".to_owned()));
+    ///
+    /// let r = parser.run_inner(&["--run", "app"]).unwrap();
+    /// assert_eq!(r, (false, "app".to_owned()));
+    ///
+    /// let r = parser.run_inner(&["--run=--fast app"]).unwrap_err().unwrap_stderr();
+    /// assert_eq!(r, "--run: `--fast` is not expected in this context");
+    /// ```
+    #[must_use]
+    pub fn argument_parsed_by<U>(
+        self,
+        metavar: &'static str,
+        inner: OptionParser<U>,
+    ) -> impl Parser<U>
+    where
+        U: 'static,
+    {
+        ParseArgumentBy {
+            inner: build_argument::<String>(self, metavar),
+            parser: inner,
+        }
+    }
+
+    /// Argument that can be repeated to build a layered list of `KEY=VALUE` overrides
+    ///
+    /// Each occurrence of the flag takes a single `KEY=VALUE` item, splits it at the first `=`
+    /// and parses both halves with [`FromStr`]. The result contains one `(key, value, position)`
+    /// triple per occurrence, in the order they are present on the command line, so when the same
+    /// key is given several times the last one should win. Flag can be absent, in which case the
+    /// result is empty. Item without `=` is an error.
+    ///
+    /// `position` tells where the value came from and is the index of the value among the
+    /// command line items after the program name, as `bpaf` sees them. It matches the word index
+    /// for values given as a separate word: `--set a=1` gives position `1`, but flags with
+    /// attached values (`--set=a=1`, `-sa=1`) and short flag groups take more than one item each
+    /// so positions should only be used to order and compare values with each other, not to
+    /// index into [`std::env::args`]. A value taken from an environment variable, which is used
+    /// only when the flag is absent from the command line, gets position `0`.
+    ///
+    /// ```rust
+    /// # use bpaf::*;
+    /// let set = long("set")
+    ///     .help("Override a config value")
+    ///     .collect_layered::<String, u32>("KEY=VALUE");
+    /// let verbose = short('v').switch();
+    /// let parser = construct!(verbose, set).to_options();
+    ///
+    /// let r = parser
+    ///     .run_inner(&["--set", "jobs=4", "-v", "--set", "depth=2", "--set", "jobs=8"])
+    ///     .unwrap();
+    /// assert_eq!(
+    ///     r.1,
+    ///     vec![
+    ///         ("jobs".to_owned(), 4, 1),
+    ///         ("depth".to_owned(), 2, 4),
+    ///         ("jobs".to_owned(), 8, 6),
+    ///     ]
+    /// );
+    /// ```
+    #[must_use]
+    pub fn collect_layered<K, V>(self, metavar: &'static str) -> impl Parser<Vec<(K, V, usize)>>
+    where
+        K: FromStr + 'static,
+        <K as FromStr>::Err: std::fmt::Display,
+        V: FromStr + 'static,
+        <V as FromStr>::Err: std::fmt::Display,
+    {
+        ParseMany {
+            inner: ParseLayered {
+                inner: build_argument::<String>(self, metavar),
+                ty: PhantomData,
+            },
+            catch: false,
+        }
+    }
+
+    /// Argument that takes everything after the flag as a single string
+    ///
+    /// Similar to `sh -c "..."`: once the flag is found on a command line all the items after it
+    /// are consumed and joined with a single space: `-c echo "hello world"` produces
+    /// `echo hello world`. Items are joined as passed by the shell, quotes removed by the shell
+    /// are not restored. Items that look like flags are included as is. `--` right after the
+    /// flag is consumed by `bpaf` as usual so `-c -- ls -la` produces `ls -la`, any `--` after
+    /// that is a part of the value: `-c git log -- file` produces `git log -- file`. Value can
+    /// also start in the same word as the flag: `-c=ls -la`.
+    ///
+    /// Parsers consume items in the order they are listed in [`construct!`](crate::construct!),
+    /// so to keep other parsers from picking up flags that follow the flag `rest_as_string`
+    /// should be listed before them. Flag without any items after it is an error. Unlike
+    /// [`batteries::forward_args`](crate::batteries::forward_args) and other ways to capture the
+    /// tail of the command line this one produces a single string rather than a list of items.
+    ///
+    /// ```rust
+    /// # use bpaf::*;
+    /// let script = short('c').help("Script to run").rest_as_string("SCRIPT");
+    /// let verbose = short('v').switch();
+    /// let parser = construct!(script, verbose).to_options();
+    ///
+    /// let r = parser.run_inner(&["-v", "-c", "ls", "-la", "/tmp"]).unwrap();
+    /// assert_eq!(r, ("ls -la /tmp".to_owned(), true));
+    ///
+    /// let r = parser.run_inner(&["-c", "echo", "-v"]).unwrap();
+    /// assert_eq!(r, ("echo -v".to_owned(), false));
+    /// ```
+    #[must_use]
+    pub fn rest_as_string(self, metavar: &'static str) -> ParseRest {
+        ParseRest {
+            named: self,
+            metavar,
+        }
+    }
+
+    /// Argument that takes a file name or `-` for standard input
+    ///
+    /// Follows a common Unix convention where `--input -` means "read the data from stdin"
+    /// instead of from a file. Produces [`FileOrStdin::Stdin`] for `-` and
+    /// [`FileOrStdin::File`] otherwise, file names don't need to be valid utf8. Use
+    /// [`FileOrStdin::open`] to get a reader for either.
+    ///
+    /// With `autocomplete` enabled `-` is offered as a possible value next to the metavariable
+    /// when the value is empty or `-`, so the shell shows it as a hint rather than
+    /// inserting it. To complete file names as well add
+    /// [`complete_shell`](Parser::complete_shell).
+    ///
+    /// ```rust
+    /// # use bpaf::*;
+    /// let input = long("input").help("File to process").argument_or_stdin("FILE");
+    /// let parser = input.to_options();
+    ///
+    /// let r = parser.run_inner(&["--input", "-"]).unwrap();
+    /// assert_eq!(r, FileOrStdin::Stdin);
+    /// let r = parser.run_inner(&["--input", "data.txt"]).unwrap();
+    /// assert_eq!(r, FileOrStdin::File("data.txt".into()));
+    /// ```
+    #[must_use]
+    pub fn argument_or_stdin(self, metavar: &'static str) -> ParseArgumentOrStdin {
+        ParseArgumentOrStdin {
+            inner: build_argument(self, metavar),
+        }
+    }
+
     /// `adjacent` requires for the argument to be present in the same word as the flag:
     /// `-f bar` - no, `-fbar` or `-f=bar` - yes.
     pub(crate) fn matches_arg(&self, arg: &Arg, adjacent: bool) -> bool {
@@ -311,6 +579,97 @@ impl<T> OptionParser<T> {
             adjacent: false,
         }
     }
+
+    /// Parse items before `--` with this parser and pass everything after it to another one
+    ///
+    /// Useful for tools that wrap other tools with their own independent parsers:
+    /// `mytool --flag -- subtool --its-flags`. Items before `--` are parsed by `self` and
+    /// items after it are given to `other`, as if it was invoked with them directly; results are
+    /// combined into a tuple. Without `--` on the command line `other` runs with no items at all.
+    /// A second `--` goes to `other` as is and has the usual meaning there.
+    ///
+    /// Parser consumes all the items in its scope so it should be used on its own, usually
+    /// followed by [`to_options`](Parser::to_options).
+    ///
+    /// # Error reporting
+    ///
+    /// Each parser reports problems in its own context: errors, `--help` and `--version` before
+    /// `--` are handled by `self` with its description, usage and help, after `--` - by
+    /// `other`. Items after `--` are not available to `self`, so `mytool -- --help` shows help for
+    /// `other`. Usage for `other` uses the name set with [`name`](OptionParser::name), if any,
+    /// otherwise it's shown without the program name. Errors in `self` are reported first, `other`
+    /// only runs once `self` succeeds.
+    ///
+    /// ```rust
+    /// # use bpaf::*;
+    /// let dry_run = long("dry-run").switch();
+    /// let mytool = construct!(dry_run).to_options();
+    ///
+    /// let verbose = short('v').switch();
+    /// let file = positional::<String>("FILE");
+    /// let subtool = construct!(verbose, file).to_options().name("subtool");
+    ///
+    /// let parser = mytool.then_parse(subtool).to_options();
+    ///
+    /// let r = parser.run_inner(&["--dry-run", "--", "-v", "Cargo.toml"]).unwrap();
+    /// assert_eq!(r, (true, (true, "Cargo.toml".to_owned())));
+    ///
+    /// let r = parser.run_inner(&["--", "-v"]).unwrap_err().unwrap_stderr();
+    /// assert_eq!(r, "expected `FILE`, pass `--help` for usage information");
+    /// ```
+    #[must_use]
+    pub fn then_parse<U>(self, other: OptionParser<U>) -> ParseThen<T, U> {
+        ParseThen {
+            head: self,
+            tail: other,
+        }
+    }
+}
+
+/// Parser that splits the command line at `--` between two parsers, created with
+/// [`then_parse`](OptionParser::then_parse)
+pub struct ParseThen<T, U> {
+    pub(crate) head: OptionParser<T>,
+    pub(crate) tail: OptionParser<U>,
+}
+
+impl<T, U> Parser<(T, U)> for ParseThen<T, U> {
+    fn eval(&self, args: &mut State) -> Result<(T, U), Error> {
+        let scope = args.scope();
+
+        // "--" and everything after it are stored as strictly positional items
+        let boundary = args.items[scope.clone()]
+            .iter()
+            .position(|arg| matches!(arg, Arg::PosWord(_)))
+            .map_or(scope.end, |ix| scope.start + ix);
+        let tail = args
+            .items_iter()
+            .filter(|(ix, _)| *ix >= boundary)
+            .map(|(_, arg)| arg.os_str().to_owned())
+            .collect::<Vec<_>>();
+
+        args.set_scope(scope.start..boundary);
+        let head = self.head.run_subparser(args);
+        args.set_scope(scope.clone());
+        let head = head.map_err(|e| Error(Message::ParseFailure(e)))?;
+
+        for ix in boundary..scope.end {
+            args.remove(ix);
+        }
+        args.current = None;
+        let tail = self
+            .tail
+            .run_inner(&tail[..])
+            .map_err(|e| Error(Message::ParseFailure(e)))?;
+        Ok((head, tail))
+    }
+
+    fn meta(&self) -> Meta {
+        Meta::And(vec![
+            self.head.inner.meta(),
+            Meta::Strict(Box::new(self.tail.inner.meta())),
+        ])
+    }
 }
 
 /// Builder structure for the [`command`]
@@ -482,6 +841,15 @@ impl<T> Parser<T> for ParseCommand<T> {
                     .run_subparser(args)
                     .map_err(|e| Error(Message::ParseFailure(e)))
             }
+        } else if args
+            .default_command
+            .map_or(false, |name| name == self.longs[0])
+        {
+            // default command runs on whatever is left in the current scope
+            args.path.push(self.longs[0].to_string());
+            self.subparser
+                .run_subparser(args)
+                .map_err(|e| Error(Message::ParseFailure(e)))
         } else {
             #[cfg(feature = "autocomplete")]
             args.push_command(self.longs[0], self.shorts.first().copied(), &self.help);
@@ -520,6 +888,7 @@ where
         present,
         absent,
         named,
+        default_str: None,
     }
 }
 
@@ -529,6 +898,7 @@ pub struct ParseFlag<T> {
     present: T,
     absent: Option<T>,
     named: NamedArg,
+    default_str: Option<&'static str>,
 }
 
 impl<T: Clone + 'static> Parser<T> for ParseFlag<T> {
@@ -565,7 +935,11 @@ impl<T: Clone + 'static> Parser<T> for ParseFlag<T> {
 
     fn meta(&self) -> Meta {
         if let Some(item) = self.named.flag_item() {
-            item.required(self.absent.is_none())
+            let meta = item.required(self.absent.is_none());
+            match self.default_str {
+                Some(default) => Meta::Suffix(Box::new(meta), Box::new(Doc::from(default))),
+                None => meta,
+            }
         } else {
             Meta::Skip
         }
@@ -586,6 +960,51 @@ impl<T> ParseFlag<T> {
     }
 }
 
+impl ParseFlag<bool> {
+    /// Show what value this flag produces when it's absent in `--help`
+    ///
+    /// By default `--help` only lists the flag name, with `help_default(true)` it also shows
+    /// `[default: off]` for a [`switch`](NamedArg::switch) or for a
+    /// [`flag`](NamedArg::flag) that produces `false` when absent and `[default: on]` for a flag
+    /// that produces `true` when absent, similar to
+    /// [`display_fallback`](crate::parsers::ParseFallback::display_fallback) for arguments.
+    /// Required flags created with [`req_flag`](NamedArg::req_flag) have no default value and
+    /// are not affected.
+    ///
+    /// Shown value describes the value flag produces, not whether it's passed: an inverted flag
+    /// such as `--no-color` created with `flag(false, true)` shows `[default: on]`, so make
+    /// sure the help message makes it clear what `on` refers to.
+    ///
+    /// ```rust
+    /// # use bpaf::*;
+    /// let verbose = long("verbose")
+    ///     .help("Print more details")
+    ///     .switch()
+    ///     .help_default(true);
+    /// let parser = verbose.to_options();
+    ///
+    /// let r = parser.run_inner(&["--help"]).unwrap_err().unwrap_stdout();
+    /// let expected = "\
+    /// Usage: [--verbose]
+    ///
+    /// Available options:
+    ///         --verbose  Print more details
+    ///                    [default: off]
+    ///     -h, --help     Prints help information
+    /// ";
+    /// assert_eq!(r, expected);
+    /// ```
+    #[must_use]
+    pub fn help_default(mut self, show: bool) -> Self {
+        self.default_str = match (show, self.absent) {
+            (true, Some(true)) => Some("[default: on]"),
+            (true, Some(false)) => Some("[default: off]"),
+            (false, _) | (true, None) => None,
+        };
+        self
+    }
+}
+
 impl<T> ParseArgument<T> {
     /// Add a help message to an `argument`
     ///
@@ -606,6 +1025,39 @@ fn build_argument<T>(named: NamedArg, metavar: &'static str) -> ParseArgument<T>
         metavar,
         ty: PhantomData,
         adjacent: false,
+        hyphen_values: false,
+        sep: HelpSep::default(),
+        possible: None,
+        shown: None,
+    }
+}
+
+/// How to separate a flag from its value in help and usage, see
+/// [`help_separator`](ParseArgument::help_separator)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HelpSep {
+    /// `-o ARG`, `--output ARG`
+    Space,
+    /// `-o=ARG`, `--output=ARG`, default
+    Equals,
+    /// `-oARG`, long names still use `=`: `--output=ARG`
+    Adjacent,
+}
+
+impl Default for HelpSep {
+    fn default() -> Self {
+        HelpSep::Equals
+    }
+}
+
+impl HelpSep {
+    pub(crate) fn as_str(self, long: bool) -> &'static str {
+        match self {
+            HelpSep::Space => " ",
+            HelpSep::Equals => "=",
+            HelpSep::Adjacent if long => "=",
+            HelpSep::Adjacent => "",
+        }
     }
 }
 
@@ -616,6 +1068,11 @@ pub struct ParseArgument<T> {
     named: NamedArg,
     metavar: &'static str,
     adjacent: bool,
+    hyphen_values: bool,
+    sep: HelpSep,
+    possible: Option<String>,
+    /// metavar to show in help and usage instead of `metavar`
+    shown: Option<String>,
 }
 
 impl<T> ParseArgument<T> {
@@ -634,18 +1091,107 @@ impl<T> ParseArgument<T> {
         self
     }
 
+    /// Accept values that look like flags
+    ///
+    /// By default `bpaf` refuses to take `--verbose` as a value in `--exclude --verbose` and
+    /// asks user to write `--exclude=--verbose` instead. With `allow_hyphen_values` a word that
+    /// looks like a flag right after the argument name is used as its value, including names
+    /// of other flags this parser knows about. Shell completion follows the same rule and
+    /// offers values for the argument when user starts typing a dash.
+    ///
+    /// ```rust
+    /// # use bpaf::*;
+    /// let exclude = long("exclude")
+    ///     .argument::<String>("PATTERN")
+    ///     .allow_hyphen_values()
+    ///     .many();
+    /// let verbose = long("verbose").switch();
+    /// let parser = construct!(exclude, verbose).to_options();
+    ///
+    /// let r = parser.run_inner(&["--exclude", "--verbose"]).unwrap();
+    /// assert_eq!(r, (vec!["--verbose".to_owned()], false));
+    ///
+    /// let r = parser.run_inner(&["--exclude", "target", "--verbose"]).unwrap();
+    /// assert_eq!(r, (vec!["target".to_owned()], true));
+    /// ```
+    #[must_use]
+    pub fn allow_hyphen_values(mut self) -> Self {
+        self.hyphen_values = true;
+        self
+    }
+
+    /// Change how the flag and its value are separated in help and usage
+    ///
+    /// By default `bpaf` renders arguments as `-o=ARG`, with this you can make help match
+    /// the way you expect users to type it. This is purely presentational, `bpaf` accepts all
+    /// the forms regardless, unless restricted with [`adjacent`](ParseArgument::adjacent).
+    ///
+    /// ```rust
+    /// # use bpaf::*;
+    /// let parser = short('o')
+    ///     .help("Output file")
+    ///     .argument::<String>("FILE")
+    ///     .help_separator(HelpSep::Space)
+    ///     .to_options();
+    ///
+    /// let help = parser.run_inner(&["--help"]).unwrap_err().unwrap_stdout();
+    /// assert!(help.starts_with("Usage: -o FILE"));
+    /// assert!(help.contains("    -o FILE     Output file"));
+    /// ```
+    #[must_use]
+    pub fn help_separator(mut self, sep: HelpSep) -> Self {
+        self.sep = sep;
+        self
+    }
+
+    /// List values this argument accepts in `--help`
+    ///
+    /// Adds `[possible values: json, text]` line under the argument's help message. This is
+    /// purely presentational, `bpaf` doesn't check the values: combine it with
+    /// [`parse`](Parser::parse) or [`guard`](Parser::guard) to reject anything else or use
+    /// [`batteries::complete_values`](crate::batteries::complete_values) that does both and
+    /// completes the values too.
+    ///
+    /// ```rust
+    /// # use bpaf::*;
+    /// let parser = long("format")
+    ///     .help("Output format")
+    ///     .argument::<String>("FMT")
+    ///     .possible_values(&["json", "text"])
+    ///     .to_options();
+    ///
+    /// let help = parser.run_inner(&["--help"]).unwrap_err().unwrap_stdout();
+    /// assert!(help.contains("Output format\n                      [possible values: json, text]\n"));
+    /// ```
+    #[must_use]
+    pub fn possible_values(mut self, values: &[&str]) -> Self {
+        self.possible = Some(format!("[possible values: {}]", values.join(", ")));
+        self
+    }
+
     fn item(&self) -> Option<Item> {
+        let metavar = match &self.shown {
+            Some(shown) => shown.clone().into(),
+            None => self.metavar.into(),
+        };
         Some(Item::Argument {
             name: ShortLong::try_from(&self.named).ok()?,
-            metavar: Metavar(self.metavar),
+            metavar: Metavar(metavar),
             env: self.named.env.first().copied(),
             help: self.named.help.clone(),
             shorts: self.named.short.clone(),
+            longs: self.named.long.clone(),
+            sep: self.sep,
         })
     }
 
     fn take_argument(&self, args: &mut State) -> Result<OsString, Error> {
-        match args.take_arg(&self.named, self.adjacent, Metavar(self.metavar)) {
+        match args.take_arg(
+            &self.named,
+            self.adjacent,
+            self.hyphen_values,
+            Metavar(self.metavar.into()),
+        ) {
             Ok(Some(w)) => {
                 #[cfg(feature = "autocomplete")]
                 if args.touching_last_remove() {
@@ -696,6 +1242,326 @@ where
         }
     }
 
+    fn meta(&self) -> Meta {
+        match (self.item(), &self.possible) {
+            (Some(item), Some(possible)) => Meta::Suffix(
+                Box::new(Meta::from(item)),
+                Box::new(Doc::from(possible.as_str())),
+            ),
+            (Some(item), None) => Meta::from(item),
+            (None, _) => Meta::Skip,
+        }
+    }
+}
+
+/// Value produced by [`argument_or_stdin`](NamedArg::argument_or_stdin)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FileOrStdin {
+    /// User passed `-`, data should be read from standard input
+    Stdin,
+    /// User passed a file name
+    File(PathBuf),
+}
+
+impl FileOrStdin {
+    /// Open the file or standard input for reading
+    ///
+    /// Standard input is returned as an unlocked [`Stdin`](std::io::Stdin) handle, it locks
+    /// itself for every read, wrap it into [`BufReader`](std::io::BufReader) for line by line
+    /// reading.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file can't be opened
+    pub fn open(&self) -> std::io::Result<Box<dyn std::io::Read>> {
+        match self {
+            FileOrStdin::Stdin => Ok(Box::new(std::io::stdin())),
+            FileOrStdin::File(path) => Ok(Box::new(std::fs::File::open(path)?)),
+        }
+    }
+}
+
+impl From<OsString> for FileOrStdin {
+    fn from(value: OsString) -> Self {
+        if value == "-" {
+            FileOrStdin::Stdin
+        } else {
+            FileOrStdin::File(PathBuf::from(value))
+        }
+    }
+}
+
+/// Parser for a named argument that accepts `-` for standard input, created with
+/// [`argument_or_stdin`](NamedArg::argument_or_stdin).
+#[derive(Clone)]
+pub struct ParseArgumentOrStdin {
+    inner: ParseArgument<FileOrStdin>,
+}
+
+impl ParseArgumentOrStdin {
+    /// Restrict parsed arguments to have both flag and a value in the same word
+    ///
+    /// See [`ParseArgument::adjacent`]
+    #[must_use]
+    pub fn adjacent(mut self) -> Self {
+        self.inner.adjacent = true;
+        self
+    }
+
+    /// Change how the flag and its value are separated in help and usage
+    ///
+    /// See [`ParseArgument::help_separator`]
+    #[must_use]
+    pub fn help_separator(mut self, sep: HelpSep) -> Self {
+        self.inner.sep = sep;
+        self
+    }
+}
+
+impl Parser<FileOrStdin> for ParseArgumentOrStdin {
+    fn eval(&self, args: &mut State) -> Result<FileOrStdin, Error> {
+        let os = self.inner.take_argument(args)?;
+
+        #[cfg(feature = "autocomplete")]
+        if args.touching_last_remove() && (os.is_empty() || os == "-") {
+            let depth = args.depth();
+            if let Some(comp) = args.comp_mut() {
+                let help = Some("Read from standard input".to_owned());
+                comp.push_value("-".to_owned(), help, None, depth, true);
+            }
+        }
+
+        Ok(FileOrStdin::from(os))
+    }
+
+    fn meta(&self) -> Meta {
+        if let Some(item) = self.inner.item() {
+            Meta::from(item)
+        } else {
+            Meta::Skip
+        }
+    }
+}
+
+/// Parser for a single `KEY=VALUE` item with its position, used by
+/// [`collect_layered`](NamedArg::collect_layered)
+struct ParseLayered<K, V> {
+    inner: ParseArgument<String>,
+    ty: PhantomData<(K, V)>,
+}
+
+impl<K, V> Parser<(K, V, usize)> for ParseLayered<K, V>
+where
+    K: FromStr + 'static,
+    <K as FromStr>::Err: std::fmt::Display,
+    V: FromStr + 'static,
+    <V as FromStr>::Err: std::fmt::Display,
+{
+    fn eval(&self, args: &mut State) -> Result<(K, V, usize), Error> {
+        let item = self.inner.eval(args)?;
+        let fail = |msg: String| Error(Message::ParseFailed(args.current, msg));
+        let (key, val) = match item.split_once('=') {
+            Some(pair) => pair,
+            None => return Err(fail("expected `=` between a key and a value".to_owned())),
+        };
+        let key = key.parse::<K>().map_err(|e| fail(e.to_string()))?;
+        let val = val.parse::<V>().map_err(|e| fail(e.to_string()))?;
+        Ok((key, val, args.current.unwrap_or(0)))
+    }
+
+    fn meta(&self) -> Meta {
+        self.inner.meta()
+    }
+}
+/// Parser for a value parsed by a nested parser, used by
+/// [`argument_parsed_by`](NamedArg::argument_parsed_by)
+struct ParseArgumentBy<U> {
+    inner: ParseArgument<String>,
+    parser: OptionParser<U>,
+}
+
+impl<U> Parser<U> for ParseArgumentBy<U> {
+    fn eval(&self, args: &mut State) -> Result<U, Error> {
+        let value = self.inner.eval(args)?;
+        let words = match split_words(&value) {
+            Ok(words) => words,
+            Err(err) => return Err(Error(Message::ParseFailed(args.current, err))),
+        };
+
+        // flag goes right before its value, value taken from env has no flag
+        let flag = match args.current {
+            Some(ix) => ix
+                .checked_sub(1)
+                .and_then(|ix| args.items.get(ix))
+                .map(ToString::to_string),
+            None => self
+                .inner
+                .named
+                .env
+                .iter()
+                .find(|name| std::env::var_os(name).is_some())
+                .map(|name| (*name).to_owned()),
+        };
+
+        let failure = match self.parser.run_inner(&words[..]) {
+            Ok(res) => return Ok(res),
+            Err(failure) => failure,
+        };
+        let prefixed = |doc: Doc| {
+            let mut res = Doc::default();
+            if let Some(flag) = &flag {
+                res.literal(flag);
+                res.text(": ");
+            }
+            res.doc(&doc);
+            res
+        };
+        let failure = match failure {
+            ParseFailure::Stderr(doc, code) => ParseFailure::Stderr(prefixed(doc), code),
+            other @ (ParseFailure::Stdout(..) | ParseFailure::Completion(_)) => other,
+        };
+        Err(Error(Message::ParseFailure(failure)))
+    }
+
+    fn meta(&self) -> Meta {
+        self.inner.meta()
+    }
+}
+
+/// Split a string into words using simplified shell rules,
+/// see [`argument_parsed_by`](NamedArg::argument_parsed_by)
+fn split_words(input: &str) -> Result<Vec<String>, String> {
+    let mut words = Vec::new();
+    let mut word = String::new();
+    let mut in_word = false;
+    let mut chars = input.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\'' => {
+                in_word = true;
+                loop {
+                    match chars.next() {
+                        Some('\'') => break,
+                        Some(c) => word.push(c),
+                        None => return Err("unterminated `'` quote".to_owned()),
+                    }
+                }
+            }
+            '"' => {
+                in_word = true;
+                loop {
+                    match chars.next() {
+                        Some('"') => break,
+                        Some('\\') => match chars.next() {
+                            Some(c @ ('"' | '\\')) => word.push(c),
+                            Some(c) => {
+                                word.push('\\');
+                                word.push(c);
+                            }
+                            None => return Err("unterminated `\"` quote".to_owned()),
+                        },
+                        Some(c) => word.push(c),
+                        None => return Err("unterminated `\"` quote".to_owned()),
+                    }
+                }
+            }
+            '\\' => {
+                in_word = true;
+                word.push(chars.next().unwrap_or('\\'));
+            }
+            c if c.is_whitespace() => {
+                if in_word {
+                    words.push(std::mem::take(&mut word));
+                    in_word = false;
+                }
+            }
+            c => {
+                in_word = true;
+                word.push(c);
+            }
+        }
+    }
+    if in_word {
+        words.push(word);
+    }
+    Ok(words)
+}
+
+/// Parser for everything after a named item, created with
+/// [`rest_as_string`](NamedArg::rest_as_string).
+#[derive(Clone)]
+pub struct ParseRest {
+    named: NamedArg,
+    metavar: &'static str,
+}
+
+impl ParseRest {
+    /// Add a help message to `rest_as_string`
+    ///
+    /// See [`NamedArg::help`]
+    #[must_use]
+    pub fn help<M>(mut self, help: M) -> Self
+    where
+        M: Into<Doc>,
+    {
+        self.named.help = Some(help.into());
+        self
+    }
+
+    fn item(&self) -> Option<Item> {
+        Some(Item::Argument {
+            name: ShortLong::try_from(&self.named).ok()?,
+            metavar: Metavar(self.metavar.into()),
+            env: self.named.env.first().copied(),
+            help: self.named.help.clone(),
+            shorts: self.named.short.clone(),
+            longs: self.named.long.clone(),
+            sep: HelpSep::default(),
+        })
+    }
+}
+
+impl Parser<String> for ParseRest {
+    fn eval(&self, args: &mut State) -> Result<String, Error> {
+        let os = match args.take_rest(&self.named, Metavar(self.metavar.into())) {
+            Ok(Some(os)) => {
+                #[cfg(feature = "autocomplete")]
+                if args.touching_last_remove() {
+                    args.push_metavar(self.metavar, &self.named.help, true);
+                }
+                os
+            }
+            Err(err) => {
+                #[cfg(feature = "autocomplete")]
+                args.push_argument(&self.named, self.metavar);
+                return Err(err);
+            }
+            Ok(None) => {
+                #[cfg(feature = "autocomplete")]
+                args.push_argument(&self.named, self.metavar);
+                if let Some(val) = self.named.env.iter().find_map(std::env::var_os) {
+                    args.current = None;
+                    val
+                } else if let Some(item) = self.item() {
+                    let missing = MissingItem {
+                        item,
+                        position: args.scope().start,
+                        scope: args.scope(),
+                    };
+                    return Err(Error(Message::Missing(vec![missing])));
+                } else if let Some(name) = self.named.env.first() {
+                    return Err(Error(Message::NoEnv(name)));
+                } else {
+                    unreachable!()
+                }
+            }
+        };
+        match parse_os_str::<String>(os) {
+            Ok(ok) => Ok(ok),
+            Err(err) => Err(Error(Message::ParseFailed(args.current, err))),
+        }
+    }
+
     fn meta(&self) -> Meta {
         if let Some(item) = self.item() {
             Meta::from(item)
@@ -797,7 +1663,7 @@ impl<T> ParsePositional<T> {
     #[inline(always)]
     fn meta(&self) -> Meta {
         let meta = Meta::from(Item::Positional {
-            metavar: Metavar(self.metavar),
+            metavar: Metavar(self.metavar.into()),
             help: self.help.clone(),
         });
         match self.position {
@@ -809,23 +1675,23 @@ impl<T> ParsePositional<T> {
 
 fn parse_pos_word(
     args: &mut State,
-    metavar: Metavar,
+    metavar: &'static str,
     help: &Option<Doc>,
     position: Position,
 ) -> Result<OsString, Error> {
-    match args.take_positional_word(metavar) {
+    match args.take_positional_word(Metavar(metavar.into())) {
         Ok((ix, is_strict, word)) => {
             match position {
                 Position::Strict => {
                     if !is_strict {
                         #[cfg(feature = "autocomplete")]
                         args.push_pos_sep();
-                        return Err(Error(Message::StrictPos(ix, metavar)));
+                        return Err(Error(Message::StrictPos(ix, Metavar(metavar.into()))));
                     }
                 }
                 Position::NonStrict => {
                     if is_strict {
-                        return Err(Error(Message::NonStrictPos(ix, metavar)));
+                        return Err(Error(Message::NonStrictPos(ix, Metavar(metavar.into()))));
                     }
                 }
                 Position::Unrestricted => {}
@@ -833,7 +1699,7 @@ fn parse_pos_word(
 
             #[cfg(feature = "autocomplete")]
             if args.touching_last_remove() && !args.check_no_pos_ahead() {
-                args.push_metavar(metavar.0, help, false);
+                args.push_metavar(metavar, help, false);
                 args.set_no_pos_ahead();
             }
             Ok(word)
@@ -841,7 +1707,7 @@ fn parse_pos_word(
         Err(err) => {
             #[cfg(feature = "autocomplete")]
             if !args.check_no_pos_ahead() {
-                args.push_metavar(metavar.0, help, false);
+                args.push_metavar(metavar, help, false);
                 args.set_no_pos_ahead();
             }
             Err(err)
@@ -855,7 +1721,7 @@ where
     <T as std::str::FromStr>::Err: std::fmt::Display,
 {
     fn eval(&self, args: &mut State) -> Result<T, Error> {
-        let os = parse_pos_word(args, Metavar(self.metavar), &self.help, self.position)?;
+        let os = parse_pos_word(args, self.metavar, &self.help, self.position)?;
         match parse_os_str::<T>(os) {
             Ok(ok) => Ok(ok),
             Err(err) => Err(Error(Message::ParseFailed(args.current, err))),
@@ -868,6 +1734,92 @@ where
     }
 }
 
+/// Parser for all the remaining positional items converted on demand, created with
+/// [`positional_stream`](crate::positional_stream)
+pub struct ParsePositionalStream<T> {
+    pub(crate) inner: ParseMany<ParsePositional<OsString>>,
+    pub(crate) ty: PhantomData<T>,
+}
+
+impl<T> ParsePositionalStream<T> {
+    /// Add a help message to a [`positional_stream`](crate::positional_stream) parser
+    ///
+    /// See [`ParsePositional::help`]
+    #[must_use]
+    pub fn help<M>(mut self, help: M) -> Self
+    where
+        M: Into<Doc>,
+    {
+        self.inner.inner.help = Some(help.into());
+        self
+    }
+}
+
+impl<T> Parser<PositionalStream<T>> for ParsePositionalStream<T> {
+    fn eval(&self, args: &mut State) -> Result<PositionalStream<T>, Error> {
+        let items = self.inner.eval(args)?;
+        Ok(PositionalStream {
+            items: items.into_iter(),
+            failed: false,
+            ty: PhantomData,
+        })
+    }
+
+    fn meta(&self) -> Meta {
+        self.inner.meta()
+    }
+}
+
+/// Iterator over positional items that converts them one by one, produced by
+/// [`positional_stream`](crate::positional_stream)
+///
+/// Yields `Err` with an error message for the first item that fails to convert and stops after
+/// that.
+#[derive(Debug)]
+pub struct PositionalStream<T> {
+    items: std::vec::IntoIter<OsString>,
+    failed: bool,
+    ty: PhantomData<T>,
+}
+
+impl<T> Iterator for PositionalStream<T>
+where
+    T: FromStr + 'static,
+    <T as std::str::FromStr>::Err: std::fmt::Display,
+{
+    type Item = Result<T, String>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.failed {
+            return None;
+        }
+        let os = self.items.next()?;
+        let lossy = os.to_string_lossy().into_owned();
+        match parse_os_str::<T>(os) {
+            Ok(ok) => Some(Ok(ok)),
+            Err(err) => {
+                self.failed = true;
+                Some(Err(format!("couldn't parse `{}`: {}", lossy, err)))
+            }
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        if self.failed {
+            (0, Some(0))
+        } else {
+            (0, self.items.size_hint().1)
+        }
+    }
+}
+
+impl<T> std::iter::FusedIterator for PositionalStream<T>
+where
+    T: FromStr + 'static,
+    <T as std::str::FromStr>::Err: std::fmt::Display,
+{
+}
+
 /// Consume an arbitrary value that satisfies a condition, created with [`any`], implements
 /// [`anywhere`](ParseAny::anywhere).
 pub struct ParseAny<T> {
@@ -947,3 +1899,136 @@ impl<T> Parser<T> for ParseAny<T> {
         Meta::Item(Box::new(self.item()))
     }
 }
+
+type SpecGuard<T> = (Rc<dyn Fn(&T) -> bool>, &'static str);
+#[cfg(feature = "autocomplete")]
+type SpecComplete<T> = Rc<dyn Fn(&T) -> Vec<(String, Option<String>)>>;
+
+/// Reusable definition of a value: metavar, help, validation and shell completion
+///
+/// When the same kind of value is taken both as a [`positional`] item and as an
+/// [`argument`](NamedArg::argument) in different parts of the app you can describe it once
+/// and use it with [`NamedArg::value`] and [`positional_spec`](crate::positional_spec).
+/// Values are parsed with [`FromStr`], checks are applied in the order they were added after
+/// the completion.
+///
+/// ```rust
+/// # use bpaf::*;
+/// fn port() -> ValueSpec<u16> {
+///     ValueSpec::new("PORT")
+///         .help("Port to use")
+///         .guard(|p| *p >= 1024, "privileged ports are not allowed")
+/// }
+///
+/// let listen = long("listen").value(port());
+/// let connect = positional_spec(port());
+/// let parser = construct!(listen, connect).to_options();
+///
+/// let r = parser.run_inner(&["--listen", "8080", "9090"]).unwrap();
+/// assert_eq!(r, (8080, 9090));
+///
+/// let r = parser.run_inner(&["--listen", "80", "9090"]).unwrap_err().unwrap_stderr();
+/// assert_eq!(r, "`80`: privileged ports are not allowed");
+/// ```
+pub struct ValueSpec<T> {
+    metavar: &'static str,
+    help: Option<Doc>,
+    guards: Vec<SpecGuard<T>>,
+    #[cfg(feature = "autocomplete")]
+    complete: Option<SpecComplete<T>>,
+}
+
+impl<T> Clone for ValueSpec<T> {
+    fn clone(&self) -> Self {
+        Self {
+            metavar: self.metavar,
+            help: self.help.clone(),
+            guards: self.guards.clone(),
+            #[cfg(feature = "autocomplete")]
+            complete: self.complete.clone(),
+        }
+    }
+}
+
+impl<T> ValueSpec<T>
+where
+    T: FromStr + 'static,
+    <T as FromStr>::Err: std::fmt::Display,
+{
+    /// Start a value definition with a metavar used in help and usage
+    #[must_use]
+    pub fn new(metavar: &'static str) -> Self {
+        Self {
+            metavar,
+            help: None,
+            guards: Vec::new(),
+            #[cfg(feature = "autocomplete")]
+            complete: None,
+        }
+    }
+
+    /// Add a help message
+    ///
+    /// Used as is by [`positional_spec`](crate::positional_spec), [`NamedArg::value`] uses it
+    /// only if the named argument doesn't have its own [`help`](NamedArg::help).
+    #[must_use]
+    pub fn help<M>(mut self, help: M) -> Self
+    where
+        M: Into<Doc>,
+    {
+        self.help = Some(help.into());
+        self
+    }
+
+    /// Add a check for parsed values
+    ///
+    /// See [`Parser::guard`]
+    #[must_use]
+    pub fn guard<F>(mut self, check: F, message: &'static str) -> Self
+    where
+        F: Fn(&T) -> bool + 'static,
+    {
+        self.guards.push((Rc::new(check), message));
+        self
+    }
+
+    /// Add dynamic shell completion
+    ///
+    /// See [`Parser::complete`]
+    #[cfg(feature = "autocomplete")]
+    #[must_use]
+    pub fn complete<F, M>(mut self, op: F) -> Self
+    where
+        F: Fn(&T) -> Vec<(M, Option<M>)> + 'static,
+        M: Into<String>,
+    {
+        self.complete = Some(Rc::new(move |t: &T| {
+            op(t)
+                .into_iter()
+                .map(|(v, h)| (v.into(), h.map(Into::into)))
+                .collect()
+        }));
+        self
+    }
+
+    pub(crate) fn positional(self) -> Box<dyn Parser<T>> {
+        let mut parser = build_positional::<T>(self.metavar);
+        parser.help = self.help.clone();
+        self.apply(parser)
+    }
+
+    fn apply<P>(self, parser: P) -> Box<dyn Parser<T>>
+    where
+        P: Parser<T> + 'static,
+    {
+        let mut parser = parser.boxed();
+        #[cfg(feature = "autocomplete")]
+        if let Some(op) = self.complete {
+            parser = parser.complete(move |t: &T| op(t)).boxed();
+        }
+        for (check, message) in self.guards {
+            parser = parser.guard(move |t: &T| check(t), message).boxed();
+        }
+        parser
+    }
+}
@@ -3,9 +3,12 @@ use crate::{
     args::State,
     buffer::MetaInfo,
     error::{Message, MissingItem},
+    item::Item,
+    meta::Header,
+    params::NamedArg,
     Doc, Error, Meta, Parser,
 };
-use std::marker::PhantomData;
+use std::{cell::RefCell, marker::PhantomData, rc::Rc};
 
 /// Parser that substitutes missing value with a function results but not parser
 /// failure, created with [`fallback_with`](Parser::fallback_with).
@@ -24,8 +27,13 @@ where
     E: ToString,
 {
     fn eval(&self, args: &mut State) -> Result<T, Error> {
+        #[cfg(feature = "autocomplete")]
+        let mut comp_items = Vec::new();
+        #[cfg(feature = "autocomplete")]
+        args.swap_comps_with(&mut comp_items);
+
         let mut clone = args.clone();
-        match self.inner.eval(&mut clone) {
+        let res = match self.inner.eval(&mut clone) {
             Ok(ok) => {
                 std::mem::swap(args, &mut clone);
                 Ok(ok)
@@ -42,7 +50,14 @@ where
                     Err(Error(e))
                 }
             }
-        }
+        };
+
+        #[cfg(feature = "autocomplete")]
+        args.swap_comps_with(&mut comp_items);
+        #[cfg(feature = "autocomplete")]
+        args.push_with_default(&self.value_str, &mut comp_items);
+
+        res
     }
 
     fn meta(&self) -> Meta {
@@ -85,7 +100,44 @@ where
 
     fn meta(&self) -> Meta {
         let meta = Box::new(self.inner.meta());
-        Meta::Subsection(meta, Box::new(self.message.clone()))
+        Meta::Subsection(meta, Header::from(self.message.clone()))
+    }
+}
+
+/// Parser with a lazily computed message attached to several fields, created with
+/// [`group_help_with`](Parser::group_help_with).
+pub struct ParseGroupHelpWith<P, F> {
+    pub(crate) inner: P,
+    pub(crate) f: Rc<F>,
+}
+
+impl<T, P, F> Parser<T> for ParseGroupHelpWith<P, F>
+where
+    P: Parser<T>,
+    F: Fn() -> Doc + 'static,
+{
+    fn eval(&self, args: &mut State) -> Result<T, Error> {
+        #[cfg(feature = "autocomplete")]
+        let mut comp_items = Vec::new();
+        #[cfg(feature = "autocomplete")]
+        args.swap_comps_with(&mut comp_items);
+
+        #[allow(clippy::let_and_return)]
+        let res = self.inner.eval(args);
+
+        #[cfg(feature = "autocomplete")]
+        args.swap_comps_with(&mut comp_items);
+        #[cfg(feature = "autocomplete")]
+        if args.comp_ref().is_some() {
+            args.push_with_group(&(self.f)().to_completion(), &mut comp_items);
+        }
+
+        res
+    }
+
+    fn meta(&self) -> Meta {
+        let meta = Box::new(self.inner.meta());
+        Meta::Subsection(meta, Header::Lazy(self.f.clone()))
     }
 }
 
@@ -108,7 +160,7 @@ where
         let meta = self.inner.meta();
         let buf = (self.f)(MetaInfo(&meta));
 
-        Meta::Subsection(Box::new(meta), Box::new(buf))
+        Meta::Subsection(Box::new(meta), Header::from(buf))
     }
 }
 
@@ -238,6 +290,88 @@ where
     }
 }
 
+/// Parser that takes values for all its items from environment variables only, created with
+/// [`Parser::env_group`]
+pub struct ParseEnvGroup<P> {
+    pub(crate) inner: P,
+}
+
+impl<T, P> Parser<T> for ParseEnvGroup<P>
+where
+    P: Parser<T>,
+{
+    fn eval(&self, args: &mut State) -> Result<T, Error> {
+        // inner parser gets to see no command line arguments so named items can only fall
+        // back to their environment variables, completions it produces are discarded too
+        let mut scratch = args.clone();
+        scratch.set_scope(0..0);
+        match self.inner.eval(&mut scratch) {
+            // missing items can only be supplied with their environment variables
+            Err(Error(Message::Missing(items))) => {
+                let env = items.iter().find_map(|m| match m.item {
+                    Item::Flag { env, .. } | Item::Argument { env, .. } => env,
+                    _ => None,
+                });
+                match env {
+                    Some(name) => Err(Error(Message::NoEnv(name))),
+                    None => Err(Error(Message::Missing(items))),
+                }
+            }
+            res => res,
+        }
+    }
+
+    fn meta(&self) -> Meta {
+        Meta::Env(Box::new(self.inner.meta()))
+    }
+}
+
+/// Parser that reuses results of previous successful evaluations on the same input, created
+/// with [`Parser::memoize`]
+pub struct ParseMemoize<P, T> {
+    pub(crate) inner: P,
+    /// state before the evaluation, produced value and state after the evaluation
+    pub(crate) cache: RefCell<Vec<(State, T, State)>>,
+}
+
+impl<P, T> Parser<T> for ParseMemoize<P, T>
+where
+    P: Parser<T>,
+    T: Clone,
+{
+    fn eval(&self, args: &mut State) -> Result<T, Error> {
+        // completion is collected as a side effect of evaluation so it can't be reused
+        #[cfg(feature = "autocomplete")]
+        if args.comp_ref().is_some() {
+            return self.inner.eval(args);
+        }
+
+        {
+            let mut cache = self.cache.borrow_mut();
+            // entries from previous parse invocations refer to a different command line
+            cache.retain(|(before, _, _)| Rc::ptr_eq(&before.items, &args.items));
+            if let Some((_, val, after)) =
+                cache.iter().find(|(before, _, _)| before.same_input(args))
+            {
+                args.replay_consumed(after);
+                return Ok(val.clone());
+            }
+        }
+
+        // cache is not borrowed here so inner parser can evaluate this one again
+        let before = args.clone();
+        let val = self.inner.eval(args)?;
+        self.cache
+            .borrow_mut()
+            .push((before, val.clone(), args.clone()));
+        Ok(val)
+    }
+
+    fn meta(&self) -> Meta {
+        self.inner.meta()
+    }
+}
+
 /// Parser that hides inner parser from usage line
 ///
 /// No other changes to the inner parser
@@ -258,6 +392,78 @@ where
     }
 }
 
+/// Parser with transformed metadata
+///
+/// Parsing is not affected, only help, usage and completion. Metadata must still describe what
+/// parser consumes, this is not exposed to users for this reason.
+pub(crate) struct ParseMapMeta<P, F> {
+    pub(crate) inner: P,
+    pub(crate) f: F,
+}
+
+impl<T, P, F> Parser<T> for ParseMapMeta<P, F>
+where
+    P: Parser<T>,
+    F: Fn(Meta) -> Meta,
+{
+    fn eval(&self, args: &mut State) -> Result<T, Error> {
+        self.inner.eval(args)
+    }
+
+    fn meta(&self) -> Meta {
+        (self.f)(self.inner.meta())
+    }
+}
+
+/// A tuple of parsers that can be used with [`at_most_one_of`](crate::at_most_one_of)
+pub trait AtMostOneOf<T> {
+    #[doc(hidden)]
+    fn at_most_one_of(self) -> Box<dyn Parser<T>>;
+}
+
+macro_rules! at_most_one_of_tuple {
+    ($($p:ident $t:ident),+) => {
+        impl<$($p, $t),+> AtMostOneOf<($(Option<$t>,)+)> for ($($p,)+)
+        where
+            $($p: Parser<$t> + 'static, $t: 'static,)+
+        {
+            #[allow(non_snake_case)]
+            fn at_most_one_of(self) -> Box<dyn Parser<($(Option<$t>,)+)>> {
+                let ($($p,)+) = self;
+                // usage should show the items as a single optional choice
+                let meta = Meta::Optional(Box::new(Meta::Or(vec![$($p.meta()),+])));
+                let names = [$({
+                    let mut name = Doc::default();
+                    match $p.meta() {
+                        Meta::Optional(meta) => name.write_meta(&meta, true),
+                        meta => name.write_meta(&meta, true),
+                    }
+                    format!("`{}`", name.monochrome(false))
+                }),+];
+                let message = format!("only one of {} may be given", names.join(", "));
+                $(let $p = $p.optional();)+
+                let inner = crate::construct!($($p),+).parse_exact(move |($($p,)+)| {
+                    if [$($p.is_some()),+].iter().filter(|p| **p).count() > 1 {
+                        Err(message.clone())
+                    } else {
+                        Ok(($($p,)+))
+                    }
+                });
+                Box::new(ParseMapMeta {
+                    inner,
+                    f: move |_| meta.clone(),
+                })
+            }
+        }
+    };
+}
+
+at_most_one_of_tuple!(PA A, PB B);
+at_most_one_of_tuple!(PA A, PB B, PC C);
+at_most_one_of_tuple!(PA A, PB B, PC C, PD D);
+at_most_one_of_tuple!(PA A, PB B, PC C, PD D, PE E);
+at_most_one_of_tuple!(PA A, PB B, PC C, PD D, PE E, PF F);
+
 /// Parser that tries to either of two parsers and uses one that succeeeds, created with
 /// [`Parser::or_else`].
 pub struct ParseOrElse<T> {
@@ -445,13 +651,15 @@ fn this_or_that_picks_first(
 }
 
 /// Parser that transforms parsed value with a failing function, created with
-/// [`parse`](Parser::parse)
+/// [`parse`](Parser::parse) or [`parse_exact`](Parser::parse_exact)
 pub struct ParseWith<T, P, F, E, R> {
     pub(crate) inner: P,
     pub(crate) inner_res: PhantomData<T>,
     pub(crate) parse_fn: F,
     pub(crate) res: PhantomData<R>,
     pub(crate) err: PhantomData<E>,
+    /// use the error message as is, without mentioning the item that failed to parse
+    pub(crate) exact: bool,
 }
 
 impl<T, P, F, E, R> Parser<R> for ParseWith<T, P, F, E, R>
@@ -464,6 +672,7 @@ where
         let t = self.inner.eval(args)?;
         match (self.parse_fn)(t) {
             Ok(r) => Ok(r),
+            Err(e) if self.exact => Err(Error(Message::ParseFailedExact(e.to_string()))),
             Err(e) => Err(Error(Message::ParseFailed(args.current, e.to_string()))),
         }
     }
@@ -487,8 +696,13 @@ where
     T: Clone,
 {
     fn eval(&self, args: &mut State) -> Result<T, Error> {
+        #[cfg(feature = "autocomplete")]
+        let mut comp_items = Vec::new();
+        #[cfg(feature = "autocomplete")]
+        args.swap_comps_with(&mut comp_items);
+
         let mut clone = args.clone();
-        match self.inner.eval(&mut clone) {
+        let res = match self.inner.eval(&mut clone) {
             Ok(ok) => {
                 std::mem::swap(args, &mut clone);
                 Ok(ok)
@@ -502,7 +716,14 @@ where
                     Err(Error(e))
                 }
             }
-        }
+        };
+
+        #[cfg(feature = "autocomplete")]
+        args.swap_comps_with(&mut comp_items);
+        #[cfg(feature = "autocomplete")]
+        args.push_with_default(&self.value_str, &mut comp_items);
+
+        res
     }
 
     fn meta(&self) -> Meta {
@@ -604,6 +825,40 @@ where
     }
 }
 
+/// Parser fails if both it and a conflicting named item are present, created with
+/// [`conflicts_with`](Parser::conflicts_with).
+pub struct ParseConflicts<P> {
+    pub(crate) inner: P,
+    pub(crate) other: NamedArg,
+}
+
+impl<T, P> Parser<T> for ParseConflicts<P>
+where
+    P: Parser<T>,
+{
+    fn eval(&self, args: &mut State) -> Result<T, Error> {
+        let before = args.clone();
+        let t = self.inner.eval(args)?;
+
+        let scope = before.scope();
+        let consumed = scope
+            .clone()
+            .find(|ix| before.present(*ix) == Some(true) && args.present(*ix) == Some(false));
+        let other = args.items[scope.clone()]
+            .iter()
+            .position(|arg| self.other.matches_arg(arg, false))
+            .map(|ix| scope.start + ix);
+        match (consumed, other) {
+            (Some(loser), Some(winner)) => Err(Error(Message::Conflict(winner, loser))),
+            _ => Ok(t),
+        }
+    }
+
+    fn meta(&self) -> Meta {
+        self.inner.meta()
+    }
+}
+
 /// Apply inner parser as many times as it succeeds while consuming something and return this
 /// number
 pub struct ParseCount<P, T> {
@@ -634,6 +889,56 @@ where
     }
 }
 
+impl<P, T> ParseCount<P, T> {
+    /// Map the number of occurrences to one of the provided values
+    ///
+    /// When the inner parser never succeeds the result is `default`, one occurrence picks the
+    /// first item of `levels`, two occurrences pick the second one and so on. Counts past the
+    /// end of `levels` saturate at the last item.
+    ///
+    /// ```rust
+    /// # use bpaf::*;
+    /// #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    /// enum Level {
+    ///     Warning,
+    ///     Info,
+    ///     Debug,
+    ///     Trace,
+    /// }
+    ///
+    /// let verbosity = short('v')
+    ///     .long("verbose")
+    ///     .help("Increase the verbosity level")
+    ///     .req_flag(())
+    ///     .count()
+    ///     .count_to_enum([Level::Info, Level::Debug, Level::Trace], Level::Warning);
+    /// let parser = verbosity.to_options();
+    ///
+    /// assert_eq!(parser.run_inner(&[]).unwrap(), Level::Warning);
+    /// assert_eq!(parser.run_inner(&["-v"]).unwrap(), Level::Info);
+    /// assert_eq!(parser.run_inner(&["-vv"]).unwrap(), Level::Debug);
+    /// assert_eq!(parser.run_inner(&["-vvvvv"]).unwrap(), Level::Trace);
+    /// ```
+    #[must_use]
+    pub fn count_to_enum<R, const N: usize>(self, levels: [R; N], default: R) -> impl Parser<R>
+    where
+        P: Parser<T>,
+        R: Clone + 'static,
+    {
+        self.map(move |count| {
+            if count == 0 {
+                default.clone()
+            } else {
+                levels
+                    .get(count - 1)
+                    .or_else(|| levels.last())
+                    .unwrap_or(&default)
+                    .clone()
+            }
+        })
+    }
+}
+
 /// Apply inner parser as many times as it succeeds while consuming something and return this
 /// number
 pub struct ParseLast<P> {
@@ -831,15 +1136,36 @@ impl<T: Clone + 'static, F: Fn() -> Result<T, E>, E: ToString> Parser<T>
     }
 }
 
-/// Parser that fails without consuming any input, created with [`fail`](crate::fail).
+/// Parser that fails without consuming any input, created with [`fail`](crate::fail) or
+/// [`fail_with_code`](crate::fail_with_code).
 pub struct ParseFail<T> {
     pub(crate) field1: &'static str,
     pub(crate) field2: PhantomData<T>,
+    pub(crate) code: Option<std::num::NonZeroU8>,
 }
 impl<T> Parser<T> for ParseFail<T> {
     fn eval(&self, args: &mut State) -> Result<T, Error> {
         args.current = None;
-        Err(Error(Message::ParseFail(self.field1)))
+        Err(Error(match self.code {
+            Some(code) => Message::ParseFailWithCode(self.field1, code),
+            None => Message::ParseFail(self.field1),
+        }))
+    }
+
+    fn meta(&self) -> Meta {
+        Meta::Skip
+    }
+}
+
+/// Parser that fails and asks to show the help message instead, created with
+/// [`fail_with_help`](crate::fail_with_help).
+pub struct ParseFailWithHelp<T> {
+    pub(crate) ty: PhantomData<T>,
+}
+impl<T> Parser<T> for ParseFailWithHelp<T> {
+    fn eval(&self, args: &mut State) -> Result<T, Error> {
+        args.current = None;
+        Err(Error(Message::ShowHelp))
     }
 
     fn meta(&self) -> Meta {
@@ -847,6 +1173,86 @@ impl<T> Parser<T> for ParseFail<T> {
     }
 }
 
+/// Parser that is also available inside of subcommands, created with
+/// [`global`](Parser::global).
+pub struct ParseGlobal<P> {
+    pub(crate) inner: Rc<P>,
+    pub(crate) global: GlobalItem,
+}
+
+impl<T, P> Parser<T> for ParseGlobal<P>
+where
+    P: Parser<T>,
+{
+    fn eval(&self, args: &mut State) -> Result<T, Error> {
+        if !args.globals.iter().any(|g| g.same(&self.global)) {
+            args.globals.push(self.global.clone());
+        }
+        self.inner.eval(args)
+    }
+
+    fn meta(&self) -> Meta {
+        self.inner.meta()
+    }
+}
+
+/// Type erased global parser, subcommands use it for help and completion
+#[derive(Clone)]
+pub(crate) struct GlobalItem(Rc<dyn Parser<()>>);
+
+impl GlobalItem {
+    pub(crate) fn new<T, P>(inner: Rc<P>) -> Self
+    where
+        P: Parser<T> + 'static,
+        T: 'static,
+    {
+        GlobalItem(Rc::new(ParseDiscard {
+            inner,
+            ty: PhantomData,
+        }))
+    }
+
+    fn same(&self, other: &Self) -> bool {
+        std::ptr::eq(
+            Rc::as_ptr(&self.0).cast::<u8>(),
+            Rc::as_ptr(&other.0).cast::<u8>(),
+        )
+    }
+
+    #[cfg(feature = "autocomplete")]
+    pub(crate) fn eval(&self, args: &mut State) -> Result<(), Error> {
+        self.0.eval(args)
+    }
+
+    pub(crate) fn meta(&self) -> Meta {
+        self.0.meta()
+    }
+}
+
+impl std::fmt::Debug for GlobalItem {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("GlobalItem").field(&self.0.meta()).finish()
+    }
+}
+
+struct ParseDiscard<T, P> {
+    inner: Rc<P>,
+    ty: PhantomData<T>,
+}
+
+impl<T, P> Parser<()> for ParseDiscard<T, P>
+where
+    P: Parser<T>,
+{
+    fn eval(&self, args: &mut State) -> Result<(), Error> {
+        self.inner.eval(args).map(|_| ())
+    }
+
+    fn meta(&self) -> Meta {
+        self.inner.meta()
+    }
+}
+
 /// Parser that transforms parsed value with a function, created with [`map`](Parser::map).
 pub struct ParseMap<T, P, F, R> {
     pub(crate) inner: P,
@@ -929,6 +1335,9 @@ impl<T> ParseCon<T> {
     /// Parsing things like `--rect --width W --height H --rect --height H --width W`
     #[cfg_attr(not(doctest), doc = include_str!("docs2/adjacent_struct_1.md"))]
     ///
+    /// When every group starts with the same delimiter flag and can be interleaved with unrelated
+    /// items you can use [`grouped_by`](ParseCon::grouped_by) instead.
+    ///
     /// # Chaining commands
     /// This example explains [`adjacent`](crate::params::ParseCommand::adjacent), but the same idea holds.
     /// Parsing things like `cmd1 --arg1 cmd2 --arg2 --arg3 cmd3 --flag`
@@ -964,8 +1373,60 @@ impl<T> ParseCon<T> {
         self.failfast = true;
         ParseAdjacent { inner: self }
     }
+
+    #[must_use]
+    /// Split the arguments into records, each one starting with a delimiter flag
+    ///
+    /// Parsing things like `--sensor --name a --bus 1 --sensor --bus 2 --name b`: every
+    /// occurrence of `delimiter` starts a new record that extends up to the next occurrence
+    /// of `delimiter` or to the end of the current scope. Inner parser runs only on the items
+    /// inside of the record, delimiter itself is consumed by `grouped_by` and shouldn't be
+    /// a part of the constructed struct. Combine it with [`many`](Parser::many) or
+    /// [`some`](Parser::some) to collect all the records. Delimiter needs a short or a long
+    /// name, one created with only [`env`](crate::env) is never present and there are no records.
+    ///
+    /// Unlike [`adjacent`](ParseCon::adjacent) items inside of the record don't need to be
+    /// next to each other: whatever inner parser doesn't consume stays available to the rest of
+    /// the parser, so `--sensor --name a --verbose --bus 1` is still a single record with
+    /// `--verbose` handled elsewhere. The record boundary is decided by the delimiter only, not
+    /// by what inner parser manages to consume, so a missing field is reported as an error
+    /// instead of silently starting a new record.
+    ///
+    /// ```rust
+    /// # use bpaf::*;
+    /// #[derive(Debug, Clone, PartialEq)]
+    /// struct Sensor {
+    ///     name: String,
+    ///     bus: usize,
+    /// }
+    ///
+    /// let name = long("name").argument::<String>("NAME");
+    /// let bus = long("bus").argument::<usize>("BUS");
+    /// let sensor = construct!(Sensor { name, bus })
+    ///     .grouped_by(long("sensor").help("Start a new sensor definition"))
+    ///     .many();
+    /// let verbose = long("verbose").switch();
+    /// let parser = construct!(sensor, verbose).to_options();
+    ///
+    /// let r = parser
+    ///     .run_inner(&["--sensor", "--bus", "1", "--verbose", "--name", "a", "--sensor", "--name", "b", "--bus", "2"])
+    ///     .unwrap();
+    /// let a = Sensor { name: "a".to_owned(), bus: 1 };
+    /// let b = Sensor { name: "b".to_owned(), bus: 2 };
+    /// assert_eq!(r, (vec![a, b], true));
+    /// ```
+    pub fn grouped_by(self, delimiter: NamedArg) -> ParseGroupedBy<Self> {
+        ParseGroupedBy {
+            inner: self,
+            delimiter,
+        }
+    }
 }
 
+/// Replacements with optional descriptions
+#[cfg(feature = "autocomplete")]
+type Suggestions = Vec<(String, Option<String>)>;
+
 /// Parser that replaces metavar placeholders with actual info in shell completion
 #[cfg(feature = "autocomplete")]
 pub struct ParseComp<P, F> {
@@ -982,6 +1443,66 @@ impl<P, F> ParseComp<P, F> {
         self.group = Some(group.into());
         self
     }
+
+    #[must_use]
+    /// Limit how long the completion function can take during a completion request
+    ///
+    /// With a timeout the completion function runs on a separate thread. If it doesn't return
+    /// within `timeout` the shell gets no candidates for this item, same as if the function
+    /// returned an empty list, and the function is left to finish in the background.
+    /// Suggestions are all or nothing: `bpaf` can't tell if a function that was stopped would
+    /// have returned more. This requires the function and the parsed value to be sendable to
+    /// another thread. Completion functions only run during a completion request so normal
+    /// parsing is not affected.
+    ///
+    /// ```rust
+    /// # use bpaf::*;
+    /// # use std::time::Duration;
+    /// fn complete_host(input: &String) -> Vec<(String, Option<String>)> {
+    ///     // a real function would be talking to the network here
+    ///     ["alpha", "beta", "gamma"]
+    ///         .iter()
+    ///         .filter(|host| host.starts_with(input.as_str()))
+    ///         .map(|host| (host.to_string(), None))
+    ///         .collect()
+    /// }
+    ///
+    /// let host = long("host")
+    ///     .argument::<String>("HOST")
+    ///     .complete(complete_host)
+    ///     .timeout(Duration::from_millis(200));
+    /// # drop(host);
+    /// ```
+    pub fn timeout<T, M>(
+        self,
+        timeout: std::time::Duration,
+    ) -> ParseComp<P, impl Fn(&T) -> Suggestions>
+    where
+        F: Fn(&T) -> Vec<(M, Option<M>)> + Send + Sync + 'static,
+        T: Clone + Send + 'static,
+        M: Into<String>,
+    {
+        let op = std::sync::Arc::new(self.op);
+        let op = move |value: &T| {
+            let (tx, rx) = std::sync::mpsc::channel();
+            let op = op.clone();
+            let value = value.clone();
+            std::thread::spawn(move || {
+                let suggestions = op(&value)
+                    .into_iter()
+                    .map(|(r, d)| (r.into(), d.map(Into::into)))
+                    .collect::<Vec<_>>();
+                // nobody is listening if the deadline passed already
+                let _ = tx.send(suggestions);
+            });
+            rx.recv_timeout(timeout).unwrap_or_default()
+        };
+        ParseComp {
+            inner: self.inner,
+            op,
+            group: self.group,
+        }
+    }
 }
 
 #[cfg(feature = "autocomplete")]
@@ -1081,19 +1602,28 @@ where
     fn eval(&self, args: &mut State) -> Result<T, Error> {
         let original_scope = args.scope();
 
-        let first_item;
         let inner_meta = self.inner.meta();
-        let mut best_error = if let Some(item) = Meta::first_item(&inner_meta) {
-            first_item = item;
-            let missing_item = MissingItem {
-                item: item.clone(),
-                position: original_scope.start,
-                scope: original_scope.clone(),
-            };
-            Message::Missing(vec![missing_item])
-        } else {
-            unreachable!("bpaf usage BUG: adjacent should start with a required argument");
+        let mut first_items = Vec::new();
+        Meta::first_items(&inner_meta, &mut first_items);
+        // block can start with any of the alternatives, an argument needs a wider window
+        let first_item = match first_items
+            .iter()
+            .find(|item| matches!(item, Item::Argument { .. }))
+            .or_else(|| first_items.first())
+        {
+            Some(item) => *item,
+            None => unreachable!("bpaf usage BUG: adjacent should start with a required argument"),
         };
+        let mut best_error = Message::Missing(
+            first_items
+                .iter()
+                .map(|item| MissingItem {
+                    item: (*item).clone(),
+                    position: original_scope.start,
+                    scope: original_scope.clone(),
+                })
+                .collect(),
+        );
         let mut best_args = args.clone();
         let mut best_consumed = 0;
 
@@ -1169,6 +1699,69 @@ where
     }
 }
 
+/// Parser that splits arguments into records starting with a delimiter flag, created with
+/// [`grouped_by`](ParseCon::grouped_by)
+pub struct ParseGroupedBy<P> {
+    pub(crate) inner: P,
+    pub(crate) delimiter: NamedArg,
+}
+
+impl<P, T> Parser<T> for ParseGroupedBy<P>
+where
+    P: Parser<T> + Sized,
+{
+    fn eval(&self, args: &mut State) -> Result<T, Error> {
+        let original_scope = args.scope();
+        let mut delimiters = args
+            .items_iter()
+            .filter(|arg| self.delimiter.matches_arg(arg.1, false))
+            .map(|arg| arg.0);
+
+        let start = if let Some(start) = delimiters.next() {
+            start
+        } else {
+            #[cfg(feature = "autocomplete")]
+            args.push_flag(&self.delimiter);
+            return match self.delimiter.flag_item() {
+                Some(item) => {
+                    let missing = MissingItem {
+                        item,
+                        position: original_scope.start,
+                        scope: original_scope,
+                    };
+                    Err(Error(Message::Missing(vec![missing])))
+                }
+                // delimiter without a name can't be present on a command line, same as
+                // env-only flags
+                None => Err(Error(Message::Missing(Vec::new()))),
+            };
+        };
+        let end = delimiters.next().unwrap_or(original_scope.end);
+
+        let mut this_arg = args.clone();
+        this_arg.set_scope(start..end);
+        this_arg.take_flag(&self.delimiter);
+        #[cfg(feature = "autocomplete")]
+        if this_arg.touching_last_remove() {
+            this_arg.push_flag(&self.delimiter);
+        }
+
+        // delimiter is consumed even if inner parser fails so `many` reports the error
+        // instead of stopping on a partially parsed record
+        let res = self.inner.eval(&mut this_arg);
+        std::mem::swap(args, &mut this_arg);
+        args.set_scope(original_scope);
+        res
+    }
+
+    fn meta(&self) -> Meta {
+        match self.delimiter.flag_item() {
+            Some(item) => Meta::And(vec![item.required(true), self.inner.meta()]),
+            None => self.inner.meta(),
+        }
+    }
+}
+
 impl<T> Parser<T> for Box<dyn Parser<T>> {
     fn eval(&self, args: &mut State) -> Result<T, Error> {
         self.as_ref().eval(args)
@@ -94,3 +94,88 @@ fn de_yoda() {
     let r = parser.run_inner(&["-a", "-b"]).unwrap();
     assert_eq!(r, (true, true));
 }
+
+#[test]
+fn explicit_color_choice_ignores_terminal() {
+    use crate::{buffer::Color, ColorChoice};
+    assert_eq!(Color::choose(ColorChoice::Never, false), Color::Monochrome);
+    assert_eq!(Color::choose(ColorChoice::Never, true), Color::Monochrome);
+
+    #[cfg(feature = "color")]
+    assert_ne!(Color::choose(ColorChoice::Always, true), Color::Monochrome);
+    #[cfg(not(feature = "color"))]
+    assert_eq!(Color::choose(ColorChoice::Always, true), Color::Monochrome);
+}
+
+#[test]
+fn pager_command_selection() {
+    use crate::info::pager_command;
+    assert_eq!(pager_command(None), Some(vec!["less"]));
+    assert_eq!(pager_command(Some("most")), Some(vec!["most"]));
+    assert_eq!(pager_command(Some(" less  -R ")), Some(vec!["less", "-R"]));
+    assert_eq!(pager_command(Some("")), None);
+    assert_eq!(pager_command(Some("  ")), None);
+}
+
+#[test]
+fn pager_fallback() {
+    use crate::{info::page_message, Doc};
+    let msg = Doc::from("help");
+    // paging is disabled or pager can't be started, caller prints the message as usual
+    assert!(!page_message(&msg, false, 100, Some("")));
+    assert!(!page_message(
+        &msg,
+        false,
+        100,
+        Some("bpaf-pager-that-does-not-exist")
+    ));
+}
+
+#[cfg(unix)]
+#[test]
+fn pager_exit_status() {
+    use crate::{info::page_message, Doc};
+    let msg = Doc::from("help");
+    // pager that fails doesn't show anything, caller prints the message as usual
+    assert!(!page_message(&msg, false, 100, Some("false")));
+    assert!(page_message(&msg, false, 100, Some("true")));
+}
+
+#[test]
+fn pager_skips_version() {
+    use crate::{info::is_version, *};
+    let parser = short('a').switch().to_options().version("1.0");
+    let version = parser.run_inner(&["--version"]).unwrap_err();
+    let help = parser.run_inner(&["--help"]).unwrap_err();
+    match (version, help) {
+        (ParseFailure::Stdout(version, _), ParseFailure::Stdout(help, _)) => {
+            assert!(is_version(&version));
+            assert!(!is_version(&help));
+        }
+        _ => panic!("expected version and help messages"),
+    }
+}
+
+#[test]
+fn map_meta_changes_help_but_not_parsing() {
+    use crate::{construct, short, structs::ParseMapMeta, Meta, Parser};
+    let verbose = short('v').help("Be verbose").switch();
+    let debug = ParseMapMeta {
+        inner: short('d').help("Debug mode").switch(),
+        f: |_| Meta::Skip,
+    };
+    let parser = construct!(verbose, debug).to_options();
+
+    let r = parser.run_inner(&["-d"]).unwrap();
+    assert_eq!(r, (false, true));
+
+    let r = parser.run_inner(&["--help"]).unwrap_err().unwrap_stdout();
+    let expected = "\
+Usage: [-v]
+
+Available options:
+    -v          Be verbose
+    -h, --help  Prints help information
+";
+    assert_eq!(r, expected);
+}
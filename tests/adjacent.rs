@@ -213,3 +213,110 @@ fn two_adjacent_args() {
         .unwrap_stderr();
     assert_eq!(r, "expected `-y=Y`, pass `--help` for usage information");
 }
+
+#[test]
+fn grouped_by_delimiter_flag() {
+    #[derive(Debug, Clone, PartialEq)]
+    struct Sensor {
+        device: String,
+        name: String,
+        bus_id: usize,
+    }
+
+    let device = long("sensor-device").argument::<String>("DEVICE");
+    let name = long("sensor-name").argument::<String>("NAME");
+    let bus_id = long("sensor-i2c-bus").argument::<usize>("BUS");
+    let sensors = construct!(Sensor {
+        device,
+        name,
+        bus_id
+    })
+    .grouped_by(long("sensor"))
+    .many();
+    let verbose = short('v').switch();
+    let parser = construct!(sensors, verbose).to_options();
+
+    let r = parser
+        .run_inner(&[
+            "--sensor",
+            "--sensor-device=tmp102",
+            "--sensor-name=outdoor",
+            "-v",
+            "--sensor-i2c-bus=0",
+            "--sensor",
+            "--sensor-i2c-bus=1",
+            "--sensor-name=indoor",
+            "--sensor-device=tmp102",
+        ])
+        .unwrap();
+    let outdoor = Sensor {
+        device: "tmp102".to_owned(),
+        name: "outdoor".to_owned(),
+        bus_id: 0,
+    };
+    let indoor = Sensor {
+        device: "tmp102".to_owned(),
+        name: "indoor".to_owned(),
+        bus_id: 1,
+    };
+    assert_eq!(r, (vec![outdoor, indoor], true));
+
+    let r = parser.run_inner(&[]).unwrap();
+    assert_eq!(r, (Vec::new(), false));
+
+    // fields can't leak from one record into another
+    let r = parser
+        .run_inner(&[
+            "--sensor",
+            "--sensor-device=tmp102",
+            "--sensor-name=outdoor",
+            "--sensor",
+            "--sensor-i2c-bus=1",
+            "--sensor-name=indoor",
+            "--sensor-device=tmp102",
+            "--sensor-i2c-bus=0",
+        ])
+        .unwrap_err()
+        .unwrap_stderr();
+    assert_eq!(
+        r,
+        "expected `--sensor-i2c-bus=BUS`, pass `--help` for usage information"
+    );
+
+    let r = parser.run_inner(&["--help"]).unwrap_err().unwrap_stdout();
+    let expected = "\
+Usage: [--sensor --sensor-device=DEVICE --sensor-name=NAME --sensor-i2c-bus=BUS]... [-v]
+
+Available options:
+        --sensor
+        --sensor-device=DEVICE
+        --sensor-name=NAME
+        --sensor-i2c-bus=BUS
+    -v
+    -h, --help                Prints help information
+";
+    assert_eq!(r, expected);
+}
+
+#[test]
+fn grouped_by_nameless_delimiter() {
+    #[derive(Debug, Clone, PartialEq)]
+    struct Sensor {
+        name: String,
+    }
+
+    let name = long("sensor-name").argument::<String>("NAME");
+    let parser = construct!(Sensor { name })
+        .grouped_by(env("SENSOR"))
+        .many()
+        .to_options();
+
+    let r = parser.run_inner(&[]).unwrap();
+    assert_eq!(r, Vec::new());
+
+    let r = parser
+        .run_inner(&["--sensor-name", "indoor"])
+        .unwrap_err()
+        .unwrap_stderr();
+    assert_eq!(r, "`--sensor-name` is not expected in this context");
+}
@@ -1,5 +1,11 @@
-use bpaf::batteries::toggle_flag;
+use bpaf::batteries::{
+    all_or_none, complete_values, enum_flags, fallback_to, forward_args, long_or_key_value,
+    range_argument, toggle_flag,
+};
+#[cfg(feature = "encoding")]
+use bpaf::batteries::{base64_bytes, hex_bytes};
 use bpaf::*;
+use std::ffi::OsString;
 
 #[test]
 fn test_toggle_flag() {
@@ -20,3 +26,374 @@ fn test_toggle_flag() {
     let r = parser.run_inner(&["-y", "-y", "-n", "-y"]).unwrap();
     assert_eq!(r, Some(Flag::Y));
 }
+
+#[test]
+fn test_all_or_none() {
+    let user = long("user").argument::<String>("USER");
+    let pass = long("pass").argument::<String>("PASS");
+    let port = long("port").argument::<u16>("PORT");
+    let verbose = short('v').switch();
+    let creds = all_or_none((user, pass, port), "--user, --pass and --port go together");
+    let parser = construct!(creds, verbose).to_options();
+
+    let r = parser.run_inner(&["-v"]).unwrap();
+    assert_eq!(r, (None, true));
+
+    let r = parser
+        .run_inner(&["--port", "22", "--user", "me", "--pass", "secret"])
+        .unwrap();
+    assert_eq!(r, (Some(("me".to_owned(), "secret".to_owned(), 22)), false));
+
+    let r = parser
+        .run_inner(&["--user", "me", "--port", "22"])
+        .unwrap_err()
+        .unwrap_stderr();
+    assert_eq!(r, "--user, --pass and --port go together");
+
+    let r = parser
+        .run_inner(&["--user", "me", "--pass", "secret", "--port", "x"])
+        .unwrap_err()
+        .unwrap_stderr();
+    assert_eq!(r, "couldn't parse `x`: invalid digit found in string");
+
+    let r = parser.run_inner(&["--help"]).unwrap_err().unwrap_stdout();
+    let expected = "\
+Usage: [--user=USER] [--pass=PASS] [--port=PORT] [-v]
+
+Available options:
+        --user=USER
+        --pass=PASS
+        --port=PORT
+    -v
+    -h, --help       Prints help information
+";
+    assert_eq!(r, expected);
+}
+
+#[test]
+fn all_or_none_with_switch() {
+    let force = long("force").switch();
+    let name = long("name").argument::<String>("NAME");
+    let parser = all_or_none((force, name), "--name is required").to_options();
+
+    let r = parser.run_inner(&["--name", "x"]).unwrap();
+    assert_eq!(r, Some((false, "x".to_owned())));
+
+    // switch always produces a value so the group is never absent
+    let r = parser.run_inner(&[]).unwrap_err().unwrap_stderr();
+    assert_eq!(r, "--name is required");
+}
+
+#[test]
+fn test_forward_args() {
+    let verbose = short('v').switch();
+    let args = forward_args();
+    let parser = construct!(verbose, args).to_options();
+
+    let r = parser.run_inner(&[]).unwrap();
+    assert_eq!(r, (false, Vec::new()));
+
+    let r = parser.run_inner(&["a", "-v", "b", "--", "-c"]).unwrap();
+    let expected = ["a", "b", "-c"]
+        .iter()
+        .map(OsString::from)
+        .collect::<Vec<_>>();
+    assert_eq!(r, (true, expected));
+
+    let r = parser.run_inner(&["a", "-c"]).unwrap_err().unwrap_stderr();
+    assert_eq!(r, "`-c` is not expected in this context");
+
+    let r = parser.run_inner(&["--help"]).unwrap_err().unwrap_stdout();
+    assert_eq!(
+        r,
+        "Usage: [-v]\n\nAvailable options:\n    -v\n    -h, --help  Prints help information\n"
+    );
+}
+
+#[test]
+fn test_fallback_to() {
+    let name = long("name").argument::<String>("NAME");
+    let title = long("title").argument::<String>("TITLE");
+    let parser = fallback_to(name, title, |n| n.to_uppercase()).to_options();
+
+    let r = parser.run_inner(&["--name", "bob"]).unwrap();
+    assert_eq!(r, ("bob".to_owned(), "BOB".to_owned()));
+
+    let r = parser
+        .run_inner(&["--title", "Dr", "--name", "bob"])
+        .unwrap();
+    assert_eq!(r, ("bob".to_owned(), "Dr".to_owned()));
+
+    let r = parser
+        .run_inner(&["--title", "Dr"])
+        .unwrap_err()
+        .unwrap_stderr();
+    assert_eq!(
+        r,
+        "expected `--name=NAME`, pass `--help` for usage information"
+    );
+
+    let r = parser.run_inner(&["--help"]).unwrap_err().unwrap_stdout();
+    assert_eq!(
+        r,
+        "Usage: --name=NAME [--title=TITLE]\n\nAvailable options:\n        --name=NAME\n        --title=TITLE\n    -h, --help         Prints help information\n"
+    );
+}
+
+#[test]
+fn test_enum_flags() {
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    enum Size {
+        Small,
+        Medium,
+        Large,
+    }
+
+    let size = enum_flags([
+        ("small", Size::Small),
+        ("medium", Size::Medium),
+        ("large", Size::Large),
+    ]);
+    let parser = size.to_options();
+
+    let r = parser.run_inner(&["--medium"]).unwrap();
+    assert_eq!(r, Size::Medium);
+
+    let r = parser
+        .run_inner(&["--small", "--large"])
+        .unwrap_err()
+        .unwrap_stderr();
+    assert_eq!(r, "`--large` cannot be used at the same time as `--small`");
+
+    let r = parser.run_inner(&[]).unwrap_err().unwrap_stderr();
+    assert_eq!(
+        r,
+        "expected `--small`, `--medium`, or more, pass `--help` for usage information"
+    );
+
+    let r = parser.run_inner(&["--help"]).unwrap_err().unwrap_stdout();
+    let expected = "\
+Usage: (--small | --medium | --large)
+
+Available options:
+        --small
+        --medium
+        --large
+    -h, --help    Prints help information
+";
+    assert_eq!(r, expected);
+}
+
+#[test]
+fn test_range_argument() {
+    let pages = range_argument::<i32>(long("page"), "PAGES").many();
+    let parser = pages.to_options();
+
+    let r = parser
+        .run_inner(&["--page", "1-3", "--page", "8", "--page=-2-0"])
+        .unwrap();
+    assert_eq!(r, vec![1..=3, 8..=8, -2..=0]);
+
+    let r = parser
+        .run_inner(&["--page", "5-1"])
+        .unwrap_err()
+        .unwrap_stderr();
+    assert_eq!(
+        r,
+        "couldn't parse `5-1`: invalid range `5-1`: start must be <= end"
+    );
+
+    let r = parser
+        .run_inner(&["--page", "1-x"])
+        .unwrap_err()
+        .unwrap_stderr();
+    assert_eq!(r, "couldn't parse `1-x`: invalid digit found in string");
+}
+
+#[test]
+fn test_long_or_key_value() {
+    let bs = long_or_key_value::<usize>("bs", "SIZE", "Block size");
+    let verbose = short('v').switch();
+    let parser = construct!(bs, verbose).to_options();
+
+    let r = parser.run_inner(&["--bs", "16"]).unwrap();
+    assert_eq!(r, (16, false));
+
+    let r = parser.run_inner(&["-v", "bs=32"]).unwrap();
+    assert_eq!(r, (32, true));
+
+    let r = parser
+        .run_inner(&["--bs", "16", "bs=32"])
+        .unwrap_err()
+        .unwrap_stderr();
+    assert_eq!(r, "`bs=32` cannot be used at the same time as `--bs`");
+
+    let r = parser.run_inner(&["bs=x"]).unwrap_err().unwrap_stderr();
+    assert_eq!(r, "couldn't parse `bs=x`: invalid digit found in string");
+
+    let r = parser.run_inner(&["--help"]).unwrap_err().unwrap_stdout();
+    let expected = "\
+Usage: (--bs=SIZE | bs=SIZE) [-v]
+
+Available options:
+        --bs=SIZE  Block size
+    bs=SIZE        Block size
+    -v
+    -h, --help     Prints help information
+";
+    assert_eq!(r, expected);
+}
+
+#[test]
+fn test_complete_values() {
+    let values = [
+        ("json", "Machine readable output"),
+        ("jsonl", "One JSON object per line"),
+        ("text", "Human readable output"),
+    ];
+    let parser = complete_values(long("format"), "FORMAT", values).to_options();
+
+    let r = parser.run_inner(&["--format", "text"]).unwrap();
+    assert_eq!(r, "text");
+
+    let r = parser
+        .run_inner(&["--format", "yaml"])
+        .unwrap_err()
+        .unwrap_stderr();
+    assert_eq!(
+        r,
+        "couldn't parse `yaml`: `yaml` is not a valid value, expected one of `json`, `jsonl`, `text`"
+    );
+
+    let r = parser
+        .run_inner(Args::from(&["--format", ""]).set_comp(0))
+        .unwrap_err()
+        .unwrap_stdout();
+    assert_eq!(
+        r,
+        "\tFORMAT\t\t\njson\tjson\t\tMachine readable output\njsonl\tjsonl\t\tOne JSON object per line\ntext\ttext\t\tHuman readable output\n\n"
+    );
+
+    let r = parser
+        .run_inner(Args::from(&["--format", "js"]).set_comp(0))
+        .unwrap_err()
+        .unwrap_stdout();
+    assert_eq!(
+        r,
+        "\tFORMAT\t\t\njson\tjson\t\tMachine readable output\njsonl\tjsonl\t\tOne JSON object per line\n\n"
+    );
+
+    let r = parser
+        .run_inner(Args::from(&["--format", "t"]).set_comp(0))
+        .unwrap_err()
+        .unwrap_stdout();
+    assert_eq!(r, "text");
+}
+
+#[test]
+fn complete_values_possible_values_in_help() {
+    let values = [
+        ("json", "Machine readable output"),
+        ("yaml", "Also machine readable output"),
+        ("toml", "Configuration file format"),
+    ];
+    let format = complete_values(long("format").help("Output format"), "FORMAT", values);
+    let parser = format
+        .fallback("json".to_owned())
+        .display_fallback()
+        .to_options();
+
+    let r = parser.run_inner(&["--help"]).unwrap_err().unwrap_stdout();
+    let expected = "\
+Usage: [--format=FORMAT]
+
+Available options:
+        --format=FORMAT  Output format
+                         [possible values: json, yaml, toml]
+                         [default: json]
+    -h, --help           Prints help information
+";
+    assert_eq!(r, expected);
+}
+
+#[test]
+#[cfg(feature = "encoding")]
+fn test_hex_bytes() {
+    let parser = hex_bytes(long("key"), "HEX").to_options();
+
+    let r = parser.run_inner(&["--key", "DEADbeef"]).unwrap();
+    assert_eq!(r, [0xde, 0xad, 0xbe, 0xef]);
+
+    let r = parser.run_inner(&["--key", "0x0100"]).unwrap();
+    assert_eq!(r, [1, 0]);
+
+    let r = parser.run_inner(&["--key="]).unwrap();
+    assert_eq!(r, []);
+
+    let r = parser
+        .run_inner(&["--key", "0xc0ge"])
+        .unwrap_err()
+        .unwrap_stderr();
+    assert_eq!(
+        r,
+        "couldn't parse `0xc0ge`: invalid hex digit `g` at position 4"
+    );
+
+    let r = parser
+        .run_inner(&["--key", "abc"])
+        .unwrap_err()
+        .unwrap_stderr();
+    assert_eq!(
+        r,
+        "couldn't parse `abc`: `abc` has an odd number of hex digits"
+    );
+}
+
+#[test]
+#[cfg(feature = "encoding")]
+fn test_base64_bytes() {
+    let parser = base64_bytes(long("data"), "BASE64").to_options();
+
+    for (input, expected) in [
+        ("", &b""[..]),
+        ("Zg==", b"f"),
+        ("Zm8=", b"fo"),
+        ("Zm9v", b"foo"),
+        ("Zm9vYg", b"foob"),
+        ("Zm9vYmE=", b"fooba"),
+        ("Zm9vYmFy", b"foobar"),
+        ("+/8=", &[0xfb, 0xff]),
+    ] {
+        let r = parser
+            .run_inner(&[String::from("--data"), input.to_owned()][..])
+            .unwrap();
+        assert_eq!(r, expected, "{}", input);
+    }
+
+    let r = parser
+        .run_inner(&["--data", "Zm-v"])
+        .unwrap_err()
+        .unwrap_stderr();
+    assert_eq!(
+        r,
+        "couldn't parse `Zm-v`: invalid base64 character `-` at position 2"
+    );
+
+    let r = parser
+        .run_inner(&["--data", "Zm9=="])
+        .unwrap_err()
+        .unwrap_stderr();
+    assert_eq!(
+        r,
+        "couldn't parse `Zm9==`: `Zm9==` has invalid base64 padding"
+    );
+
+    let r = parser
+        .run_inner(&["--data", "Zm9vY"])
+        .unwrap_err()
+        .unwrap_stderr();
+    assert_eq!(
+        r,
+        "couldn't parse `Zm9vY`: `Zm9vY` has invalid base64 length"
+    );
+}
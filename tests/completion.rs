@@ -292,6 +292,37 @@ build\tbuild\t\tbuild project\n\n"
     assert_eq!(r, "check");
 }
 
+#[test]
+fn single_dash_prefers_short_names() {
+    let a = short('a').long("alpha").help("both names").switch();
+    let b = short('b').help("short only").switch();
+    let c = long("charlie").help("long only").switch();
+    let parser = construct!(a, b, c).to_options();
+
+    let r = parser
+        .run_inner(Args::from(&["-"]).set_comp(0))
+        .unwrap_err()
+        .unwrap_stdout();
+    assert_eq!(
+        r,
+        "\
+-a\t-a\t\tboth names
+-b\t-b\t\tshort only
+--charlie\t--charlie\t\tlong only\n\n"
+    );
+
+    let r = parser
+        .run_inner(Args::from(&["--"]).set_comp(0))
+        .unwrap_err()
+        .unwrap_stdout();
+    assert_eq!(
+        r,
+        "\
+--alpha\t--alpha\t\tboth names
+--charlie\t--charlie\t\tlong only\n\n"
+    );
+}
+
 #[test]
 fn static_complete_test_3() {
     let a = long("potato").help("po").argument::<String>("P");
@@ -1583,3 +1614,400 @@ fn positionals_with_no_completions_are_not_duplicated() {
 \tBETA\t\tBeta argument\n\n"
     );
 }
+
+#[test]
+fn complete_help_and_version() {
+    let a = long("hello").help("Say hello").switch();
+    let parser = construct!(a).to_options().version("1.0");
+
+    let r = parser
+        .run_inner(Args::from(&["--h"]).set_comp(0))
+        .unwrap_err()
+        .unwrap_stdout();
+    assert_eq!(
+        r,
+        "--hello\t--hello\t\tSay hello\n--help\t--help\t\tPrints help information\n\n"
+    );
+
+    let r = parser
+        .run_inner(Args::from(&["--v"]).set_comp(0))
+        .unwrap_err()
+        .unwrap_stdout();
+    assert_eq!(r, "--version");
+
+    // only shown when user starts typing a flag
+    let r = parser
+        .run_inner(Args::from(&[""]).set_comp(0))
+        .unwrap_err()
+        .unwrap_stdout();
+    assert_eq!(r, "--hello");
+}
+
+#[test]
+fn complete_custom_help_in_command() {
+    let a = long("hello").help("Say hello").switch();
+    let cmd = construct!(a)
+        .to_options()
+        .help_parser(long("halp").help("Halp!"))
+        .command("cmd");
+    let parser = construct!(cmd).to_options();
+
+    let r = parser
+        .run_inner(Args::from(&["cmd", "--h"]).set_comp(0))
+        .unwrap_err()
+        .unwrap_stdout();
+    assert_eq!(
+        r,
+        "--hello\t--hello\t\tSay hello\n--halp\t--halp\t\tHalp!\n\n"
+    );
+
+    // no version here, parser has no version information
+    let r = parser
+        .run_inner(Args::from(&["cmd", "--v"]).set_comp(0))
+        .unwrap_err()
+        .unwrap_stdout();
+    assert_eq!(r, "--v\n");
+}
+
+#[test]
+fn complete_global_flag_in_command() {
+    let a = long("verbatim").help("Copy as is").switch();
+    let cmd = construct!(a).to_options().command("cmd");
+    let verbose = long("verbose").help("Be verbose").switch().global();
+    let parser = construct!(verbose, cmd).to_options();
+
+    let r = parser
+        .run_inner(Args::from(&["cmd", "--verb"]).set_comp(0))
+        .unwrap_err()
+        .unwrap_stdout();
+    assert_eq!(
+        r,
+        "--verbatim\t--verbatim\t\tCopy as is\n--verbose\t--verbose\t\tBe verbose\n\n"
+    );
+
+    let r = parser
+        .run_inner(Args::from(&["cmd", "--verba"]).set_comp(0))
+        .unwrap_err()
+        .unwrap_stdout();
+    assert_eq!(r, "--verbatim");
+}
+
+#[test]
+fn value_that_looks_like_a_flag() {
+    fn patterns(input: &String) -> Vec<(&'static str, Option<&'static str>)> {
+        ["--verbose", "--version", "target"]
+            .iter()
+            .filter(|p| p.starts_with(input.as_str()))
+            .map(|p| (*p, None))
+            .collect()
+    }
+
+    let exclude = long("exclude")
+        .help("Pattern to exclude")
+        .argument::<String>("PATTERN")
+        .allow_hyphen_values()
+        .complete(patterns)
+        .many();
+    let verbose = long("verbose").help("Be verbose").switch();
+    let parser = construct!(exclude, verbose).to_options();
+
+    let r = parser
+        .run_inner(Args::from(&["--exclude", "--ver"]).set_comp(0))
+        .unwrap_err()
+        .unwrap_stdout();
+    assert_eq!(
+        r,
+        "\tPATTERN\t\tPattern to exclude\n--verbose\t--verbose\t\t\n--version\t--version\t\t\n\n"
+    );
+
+    let r = parser
+        .run_inner(Args::from(&["--exclude", "-"]).set_comp(0))
+        .unwrap_err()
+        .unwrap_stdout();
+    assert_eq!(
+        r,
+        "\tPATTERN\t\tPattern to exclude\n--verbose\t--verbose\t\t\n--version\t--version\t\t\n\n"
+    );
+
+    let r = parser
+        .run_inner(Args::from(&["--exclude", "t"]).set_comp(0))
+        .unwrap_err()
+        .unwrap_stdout();
+    assert_eq!(r, "target");
+
+    // not a value for --exclude - flags as usual
+    let r = parser
+        .run_inner(Args::from(&["--exclude", "target", "--ver"]).set_comp(0))
+        .unwrap_err()
+        .unwrap_stdout();
+    assert_eq!(r, "--verbose");
+
+    // completed command line parses as completion suggested
+    let r = parser.run_inner(&["--exclude", "--verbose"]).unwrap();
+    assert_eq!(r, (vec!["--verbose".to_owned()], false));
+}
+
+#[test]
+fn completion_with_prepended_args() {
+    use std::ffi::OsString;
+    let level = long("level").argument::<u32>("L").last();
+    let verbose = long("verbose").switch();
+    let parser = construct!(level, verbose)
+        .to_options()
+        .prepend_args(&[OsString::from("--level=1"), OsString::from("--")]);
+
+    let r = parser
+        .run_inner(Args::from(&["--verb"]).set_comp(0))
+        .unwrap_err()
+        .unwrap_stdout();
+    assert_eq!(r, "--verbose");
+}
+
+#[test]
+fn value_that_looks_like_a_flag_without_hyphen_values() {
+    fn patterns(input: &String) -> Vec<(&'static str, Option<&'static str>)> {
+        ["--verbose", "--version"]
+            .iter()
+            .filter(|p| p.starts_with(input.as_str()))
+            .map(|p| (*p, None))
+            .collect()
+    }
+
+    let exclude = long("exclude")
+        .argument::<String>("PATTERN")
+        .complete(patterns)
+        .many();
+    let verbose = long("verbose").switch();
+    let parser = construct!(exclude, verbose).to_options();
+
+    // parser refuses `--exclude --verbose` so completion offers flags, not values
+    let r = parser
+        .run_inner(Args::from(&["--exclude", "--verb"]).set_comp(0))
+        .unwrap_err()
+        .unwrap_stdout();
+    assert_eq!(r, "--verbose");
+
+    let r = parser
+        .run_inner(&["--exclude", "--verbose"])
+        .unwrap_err()
+        .unwrap_stderr();
+    assert_eq!(r, "`--exclude` requires an argument `PATTERN`");
+}
+
+#[test]
+fn same_argument_in_different_commands() {
+    fn alpha(_: &String) -> Vec<(&'static str, Option<&'static str>)> {
+        vec![("alpha1", None), ("alpha2", None)]
+    }
+    fn beta(_: &String) -> Vec<(&'static str, Option<&'static str>)> {
+        vec![("beta1", None), ("beta2", None)]
+    }
+
+    let target = long("target").argument::<String>("T").complete(alpha);
+    let a = construct!(target).to_options().command("a");
+    let target = long("target").argument::<String>("T").complete(beta);
+    let b = construct!(target).to_options().command("b");
+    let parser = construct!([a, b]).to_options();
+
+    let r = parser
+        .run_inner(Args::from(&["a", "--target", ""]).set_comp(0))
+        .unwrap_err()
+        .unwrap_stdout();
+    assert_eq!(r, "\tT\t\t\nalpha1\talpha1\t\t\nalpha2\talpha2\t\t\n\n");
+
+    let r = parser
+        .run_inner(Args::from(&["b", "--target", ""]).set_comp(0))
+        .unwrap_err()
+        .unwrap_stdout();
+    assert_eq!(r, "\tT\t\t\nbeta1\tbeta1\t\t\nbeta2\tbeta2\t\t\n\n");
+}
+
+#[test]
+fn argument_or_stdin_offers_dash() {
+    let input = long("input")
+        .help("File to process")
+        .argument_or_stdin("FILE");
+    let parser = input.to_options();
+
+    let r = parser
+        .run_inner(Args::from(&["--input", ""]).set_comp(0))
+        .unwrap_err()
+        .unwrap_stdout();
+    assert_eq!(
+        r,
+        "\tFILE\t\tFile to process\n-\t-\t\tRead from standard input\n\n"
+    );
+
+    let r = parser
+        .run_inner(Args::from(&["--input", "a"]).set_comp(0))
+        .unwrap_err()
+        .unwrap_stdout();
+    assert_eq!(r, "\tFILE\t\tFile to process\n\n");
+}
+
+#[test]
+fn is_completing_during_completion_only() {
+    use std::{cell::Cell, rc::Rc};
+
+    let seen = Rc::new(Cell::new(None));
+    let seen2 = seen.clone();
+    let user = long("user")
+        .argument::<String>("USER")
+        .fallback_with(move || {
+            seen2.set(Some(is_completing()));
+            Ok::<_, String>("guest".to_owned())
+        });
+    let verbose = long("verbose").switch();
+    let parser = construct!(user, verbose).to_options();
+
+    let r = parser
+        .run_inner(Args::from(&["--verb"]).set_comp(0))
+        .unwrap_err()
+        .unwrap_stdout();
+    assert_eq!(r, "--verbose");
+    assert_eq!(seen.get(), Some(true));
+    assert!(!is_completing());
+
+    let r = parser.run_inner(&["--verbose"]).unwrap();
+    assert_eq!(r, ("guest".to_owned(), true));
+    assert_eq!(seen.get(), Some(false));
+}
+
+#[test]
+fn displayed_fallback_in_completion_help() {
+    let format = long("format")
+        .help("Output format")
+        .argument::<String>("FORMAT")
+        .fallback("json".to_owned())
+        .display_fallback();
+    let level = long("level")
+        .argument::<u32>("N")
+        .fallback_with(|| Ok::<_, String>(3))
+        .display_fallback();
+    let jobs = long("jobs").help("Jobs").argument::<u32>("J").fallback(1);
+    let parser = construct!(format, level, jobs).to_options();
+
+    let r = parser
+        .run_inner(Args::from(&["--"]).set_comp(0))
+        .unwrap_err()
+        .unwrap_stdout();
+    assert_eq!(
+        r,
+        "--format\t--format=FORMAT\t\tOutput format [default: json]\n--level\t--level=N\t\t[default: 3]\n--jobs\t--jobs=J\t\tJobs\n\n"
+    );
+
+    let r = parser
+        .run_inner(Args::from(&["--format", ""]).set_comp(0))
+        .unwrap_err()
+        .unwrap_stdout();
+    assert_eq!(r, "\tFORMAT\t\tOutput format [default: json]\n\n");
+
+    let r = parser
+        .run_inner(Args::from(&["--level", ""]).set_comp(0))
+        .unwrap_err()
+        .unwrap_stdout();
+    assert_eq!(r, "\tN\t\t[default: 3]\n\n");
+}
+
+#[test]
+fn completion_timeout() {
+    // never returns in time
+    fn slow(input: &String) -> Vec<(&'static str, Option<&'static str>)> {
+        std::thread::sleep(std::time::Duration::from_secs(60));
+        test_completer(input)
+    }
+
+    let fast = short('f')
+        .argument::<String>("FAST")
+        .complete(test_completer)
+        .timeout(std::time::Duration::from_secs(60));
+    let slow = short('s')
+        .argument::<String>("SLOW")
+        .complete(slow)
+        .timeout(std::time::Duration::from_millis(10));
+    let parser = construct!(fast, slow).to_options();
+
+    let r = parser
+        .run_inner(Args::from(&["-f", "b"]).set_comp(0))
+        .unwrap_err()
+        .unwrap_stdout();
+    assert_eq!(r, "\tFAST\t\t\nbeta\tbeta\t\t\nbanana\tbanana\t\t\n\n");
+
+    let r = parser
+        .run_inner(Args::from(&["-s", "b"]).set_comp(0))
+        .unwrap_err()
+        .unwrap_stdout();
+    assert_eq!(r, "\tSLOW\t\t\n\n");
+
+    // parsing doesn't run the completion function
+    let r = parser.run_inner(&["-s", "b", "-f", "a"]).unwrap();
+    assert_eq!(r, ("a".to_owned(), "b".to_owned()));
+}
+
+#[test]
+fn shell_completion_from_file() {
+    let parser = long("pkg")
+        .argument::<String>("PKG")
+        .complete_shell(ShellComp::FromFile {
+            path: "packages.txt",
+        })
+        .to_options();
+
+    let r = parser
+        .run_inner(Args::from(&["--pkg", ""]).set_comp(0))
+        .unwrap_err()
+        .unwrap_stdout();
+    assert_eq!(r, "\nFromFile { path: \"packages.txt\" }\n");
+}
+
+#[test]
+fn elvish_completion_from_file() {
+    const PATH: &str = concat!(env!("CARGO_TARGET_TMPDIR"), "/elvish_packages.txt");
+    const MISSING: &str = concat!(env!("CARGO_TARGET_TMPDIR"), "/elvish_missing.txt");
+    std::fs::write(PATH, "serde\tSerialization framework\n\ntokio\n").unwrap();
+    let _ = std::fs::remove_file(MISSING);
+
+    let pkg = long("pkg")
+        .argument::<String>("PKG")
+        .complete_shell(ShellComp::FromFile { path: PATH });
+    let dep = long("dep")
+        .argument::<String>("DEP")
+        .complete_shell(ShellComp::FromFile { path: MISSING });
+    let parser = construct!(pkg, dep).to_options();
+
+    let r = parser
+        .run_inner(Args::from(&["--pkg", ""]).set_comp(2))
+        .unwrap_err()
+        .unwrap_stdout();
+    assert_eq!(
+        r,
+        "serde\tserde               Serialization framework\ntokio\n"
+    );
+
+    let r = parser
+        .run_inner(Args::from(&["--dep", ""]).set_comp(2))
+        .unwrap_err()
+        .unwrap_stdout();
+    assert_eq!(r, "");
+}
+
+#[test]
+fn elvish_descriptions() {
+    let a = long("alpha").help("First\nsecond line").switch();
+    let b = long("beta").switch();
+    let c = long("gamma").argument::<String>("G");
+    let parser = construct!(a, b, c).to_options();
+
+    let r = parser
+        .run_inner(Args::from(&["--"]).set_comp(2))
+        .unwrap_err()
+        .unwrap_stdout();
+    assert_eq!(r, "--alpha\t--alpha             First\n--beta\n--gamma\n");
+
+    // metavars can't be inserted and are skipped
+    let r = parser
+        .run_inner(Args::from(&["--gamma", ""]).set_comp(2))
+        .unwrap_err()
+        .unwrap_stdout();
+    assert_eq!(r, "");
+}
@@ -135,3 +135,234 @@ fn pure_optional() {
 
     assert_eq!(opts().run().foo, None);
 }
+
+#[test]
+fn repeated_help_shows_details() {
+    #[derive(Debug, Clone, Bpaf)]
+    #[bpaf(options)]
+    /// Process some files
+    ///
+    /// Files are processed in the order they are given
+    struct Opts {
+        /// Number of threads
+        ///
+        /// Uses all available cores when not specified
+        #[bpaf(argument("N"))]
+        jobs: Option<usize>,
+    }
+
+    let r = opts().run_inner(&["--jobs", "2"]).unwrap();
+    assert_eq!(r.jobs, Some(2));
+
+    let r = opts().run_inner(&["--help"]).unwrap_err().unwrap_stdout();
+    let expected = "\
+Process some files
+
+Usage: [--jobs=N]
+
+Available options:
+        --jobs=N  Number of threads
+    -h, --help    Prints help information
+";
+    assert_eq!(r, expected);
+
+    let r = opts()
+        .run_inner(&["--help", "--help"])
+        .unwrap_err()
+        .unwrap_stdout();
+    let expected = "\
+Process some files
+Files are processed in the order they are given
+
+Usage: [--jobs=N]
+
+Available options:
+        --jobs=N  Number of threads
+                  Uses all available cores when not specified
+    -h, --help    Prints help information
+";
+    assert_eq!(r, expected);
+}
+
+#[test]
+fn external_env_group() {
+    #[derive(Debug, Clone, Bpaf, PartialEq)]
+    struct DbConfig {
+        /// Database host
+        #[bpaf(env("BPAF_TEST_DB_HOST"), argument("HOST"), fallback("localhost".to_owned()))]
+        host: String,
+        /// Log all the queries
+        #[bpaf(env("BPAF_TEST_DB_DEBUG"))]
+        debug: bool,
+    }
+
+    #[derive(Debug, Clone, Bpaf, PartialEq)]
+    #[bpaf(options)]
+    struct Opts {
+        #[bpaf(external(db_config), env_group)]
+        db: DbConfig,
+        /// Be verbose
+        verbose: bool,
+    }
+
+    let r = opts().run_inner(&["--help"]).unwrap_err().unwrap_stdout();
+    let expected = "\
+Usage: [--verbose]
+
+Available options:
+        --verbose           Be verbose
+    -h, --help              Prints help information
+
+Environment variables:
+    BPAF_TEST_DB_HOST=HOST  Database host
+    BPAF_TEST_DB_DEBUG      Log all the queries
+";
+    assert_eq!(r, expected);
+
+    let r = opts().run_inner(&["--verbose"]).unwrap();
+    assert_eq!(r.db.host, "localhost");
+    assert!(r.verbose);
+
+    let r = opts()
+        .run_inner(&["--host", "example.com"])
+        .unwrap_err()
+        .unwrap_stderr();
+    assert_eq!(r, "`--host` is not expected in this context");
+
+    std::env::set_var("BPAF_TEST_DB_HOST", "example.com");
+    let r = opts().run_inner(&[]).unwrap();
+    assert_eq!(r.db.host, "example.com");
+    assert!(!r.db.debug);
+}
+
+#[test]
+fn multiple_short_long_aliases() {
+    #[derive(Debug, Clone, Bpaf, PartialEq)]
+    #[bpaf(options)]
+    struct Opts {
+        /// Alpha value
+        #[bpaf(short('a'), short('b'), long("alpha"), long("alfa"))]
+        alpha: usize,
+        /// Be verbose
+        #[bpaf(long("verbose"), short('v'), long("loud"), short('l'))]
+        verbose: bool,
+    }
+
+    let r = opts().run_inner(&["--help"]).unwrap_err().unwrap_stdout();
+    let expected = "\
+Usage: -a=ARG [-v]
+
+Available options:
+    -a, --alpha=ARG  Alpha value
+    -v, --verbose    Be verbose
+    -h, --help       Prints help information
+";
+    assert_eq!(r, expected);
+
+    for args in [
+        &["-a", "1"][..],
+        &["-b", "1"],
+        &["--alpha", "1"],
+        &["--alfa=1"],
+        &["-b1"],
+    ] {
+        let r = opts().run_inner(args).unwrap();
+        assert_eq!(
+            r,
+            Opts {
+                alpha: 1,
+                verbose: false
+            }
+        );
+    }
+
+    for args in [&["-a1", "-v"][..], &["--loud", "-a1"], &["-la", "1"]] {
+        let r = opts().run_inner(args).unwrap();
+        assert_eq!(
+            r,
+            Opts {
+                alpha: 1,
+                verbose: true
+            }
+        );
+    }
+
+    let r = opts()
+        .run_inner(&["-a", "1", "--alfa", "2"])
+        .unwrap_err()
+        .unwrap_stderr();
+    assert_eq!(
+        r,
+        "argument `--alfa` cannot be used multiple times in this context"
+    );
+}
+
+#[test]
+fn struct_level_guard() {
+    fn valid_side(side: &u32) -> bool {
+        (1..=20).contains(side)
+    }
+
+    fn valid_area(rect: &Rectangle) -> bool {
+        rect.width * rect.height <= 200
+    }
+
+    #[derive(Debug, Clone, Bpaf, PartialEq)]
+    #[bpaf(
+        options,
+        guard(valid_area, "Area of a rectangle must not exceed 200 units square")
+    )]
+    struct Rectangle {
+        /// Width of the rectangle
+        #[bpaf(argument("PX"), guard(valid_side, "Sides must be 1..20 units long"))]
+        width: u32,
+        /// Height of the rectangle
+        #[bpaf(argument("PX"), guard(valid_side, "Sides must be 1..20 units long"))]
+        height: u32,
+    }
+
+    let r = rectangle()
+        .run_inner(&["--width", "10", "--height", "20"])
+        .unwrap();
+    assert_eq!(
+        r,
+        Rectangle {
+            width: 10,
+            height: 20
+        }
+    );
+
+    let r = rectangle()
+        .run_inner(&["--width", "11", "--height", "20"])
+        .unwrap_err()
+        .unwrap_stderr();
+    assert_eq!(
+        r,
+        "check failed: Area of a rectangle must not exceed 200 units square"
+    );
+
+    let r = rectangle()
+        .run_inner(&["--width", "0", "--height", "20"])
+        .unwrap_err()
+        .unwrap_stderr();
+    assert_eq!(r, "`0`: Sides must be 1..20 units long");
+
+    #[derive(Debug, Clone, Bpaf, PartialEq)]
+    #[bpaf(guard(|s: &Shape| !matches!(s, Shape::Square { side: 0 }), "Empty square"))]
+    enum Shape {
+        Square {
+            side: u32,
+        },
+        #[bpaf(command)]
+        Point,
+    }
+
+    let parser = shape().to_options();
+    let r = parser.run_inner(&["--side", "3"]).unwrap();
+    assert_eq!(r, Shape::Square { side: 3 });
+    let r = parser
+        .run_inner(&["--side", "0"])
+        .unwrap_err()
+        .unwrap_stderr();
+    assert_eq!(r, "check failed: Empty square");
+}
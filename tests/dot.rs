@@ -0,0 +1,42 @@
+#![cfg(feature = "docgen")]
+
+use bpaf::*;
+
+#[test]
+fn commands_and_groups() {
+    let release = long("release").switch();
+    let build = construct!(release).to_options().command("build");
+    let clean = pure(false).to_options().command("clean");
+    let cmd = construct!([build, clean]);
+    let verbose = short('v').switch();
+    let files = positional::<String>("FILE").many();
+    let parser = construct!(verbose, files, cmd).to_options();
+
+    let r = parser.render_dot();
+    let expected = r#"digraph bpaf {
+    node [fontname="monospace"];
+    n0 [label="-v", shape=box];
+    n1 [label="optional", shape=ellipse];
+    n1 -> n0;
+    n2 [label="FILE", shape=box];
+    n3 [label="optional", shape=ellipse];
+    n3 -> n2;
+    n4 [label="many", shape=ellipse];
+    n4 -> n3;
+    n5 [label="build", shape=box, style=bold];
+    n6 [label="--release", shape=box];
+    n7 [label="optional", shape=ellipse];
+    n7 -> n6;
+    n5 -> n7;
+    n8 [label="clean", shape=box, style=bold];
+    n9 [label="or", shape=diamond];
+    n9 -> n5;
+    n9 -> n8;
+    n10 [label="and", shape=ellipse];
+    n10 -> n1;
+    n10 -> n4;
+    n10 -> n9;
+}
+"#;
+    assert_eq!(r, expected);
+}
@@ -297,3 +297,106 @@ fn used_only_once_is_more_important_error() {
         "argument `--sort` cannot be used multiple times in this context"
     );
 }
+
+#[test]
+fn fail_with_custom_exit_code() {
+    let a = short('a').req_flag(1);
+    let no_a = fail_with_code("-a is required", 42);
+    let b = short('b').argument::<u32>("B").fallback(0);
+    let parser = construct!([a, no_a]);
+    let parser = construct!(parser, b).to_options();
+
+    let r = parser.run_inner(&["-a"]).unwrap();
+    assert_eq!(r, (1, 0));
+
+    let r = parser.run_inner(&[]).unwrap_err();
+    assert!(matches!(r, ParseFailure::Stderr(..)));
+    assert_eq!(r.clone().exit_code(), 42);
+    assert_eq!(r.unwrap_stderr(), "-a is required");
+
+    // other failures use the usual exit code
+    let r = parser.run_inner(&["-a", "-b", "x"]).unwrap_err();
+    assert_eq!(r.exit_code(), 1);
+}
+
+#[test]
+fn custom_exit_code_survives_nesting() {
+    let inner = fail_with_code::<()>("nope", 3).to_options();
+    let cmd = fail_with_code::<()>("nope", 4).to_options().command("cmd");
+    let run = long("run").argument_parsed_by("CMD", inner);
+    let parser = construct!([cmd, run]).to_options();
+
+    let r = parser.run_inner(&["cmd"]).unwrap_err();
+    assert_eq!(r.exit_code(), 4);
+
+    let r = parser.run_inner(&["--run", "x"]).unwrap_err();
+    assert_eq!(r.clone().exit_code(), 3);
+    assert_eq!(r.unwrap_stderr(), "--run: nope");
+}
+
+#[test]
+#[should_panic(expected = "fail_with_code needs a non zero exit code")]
+fn custom_exit_code_cant_be_zero() {
+    let _ = fail_with_code::<()>("nope", 0);
+}
+
+#[test]
+fn explicit_conflict_between_independent_flags() {
+    let verbose = short('v')
+        .long("verbose")
+        .req_flag(())
+        .many()
+        .map(|v| v.len());
+    let quiet = short('q')
+        .long("quiet")
+        .switch()
+        .conflicts_with(short('v').long("verbose"));
+    let color = long("color").switch();
+    let parser = construct!(quiet, verbose, color).to_options();
+
+    let r = parser.run_inner(&["-vv", "--color"]).unwrap();
+    assert_eq!(r, (false, 2, true));
+
+    let r = parser.run_inner(&["--quiet", "--color"]).unwrap();
+    assert_eq!(r, (true, 0, true));
+
+    // order doesn't matter
+    let r = parser
+        .run_inner(&["--quiet", "--verbose"])
+        .unwrap_err()
+        .unwrap_stderr();
+    assert_eq!(
+        r,
+        "`--quiet` cannot be used at the same time as `--verbose`"
+    );
+
+    let r = parser
+        .run_inner(&["-v", "--color", "-q"])
+        .unwrap_err()
+        .unwrap_stderr();
+    assert_eq!(r, "`-q` cannot be used at the same time as `-v`");
+
+    let r = parser.run_inner(&["-qv"]).unwrap_err().unwrap_stderr();
+    assert_eq!(r, "`-q` cannot be used at the same time as `-v`");
+}
+
+#[test]
+fn env_group_missing_variable() {
+    let host = long("host")
+        .env("BPAF_TEST_DB_HOST")
+        .argument::<String>("HOST");
+    let verbose = short('v').switch();
+    let db = construct!(host).env_group();
+    let parser = construct!(db, verbose).to_options();
+
+    let r = parser.run_inner(&[]).unwrap_err().unwrap_stderr();
+    assert_eq!(r, "environment variable `BPAF_TEST_DB_HOST` is not set");
+
+    // error can still be caught
+    let host = long("host")
+        .env("BPAF_TEST_DB_HOST")
+        .argument::<String>("HOST");
+    let parser = construct!(host).env_group().optional().to_options();
+    let r = parser.run_inner(&[]).unwrap();
+    assert_eq!(r, None);
+}
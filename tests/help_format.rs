@@ -530,6 +530,42 @@ Available options:
     assert_eq!(r, ((true, false), true));
 }
 
+#[test]
+fn group_help_with() {
+    use std::{cell::Cell, rc::Rc};
+
+    let calls = Rc::new(Cell::new(0));
+    let a = short('a').help("option a").switch();
+    let b = short('b').help("option b").switch();
+
+    let counter = calls.clone();
+    let ab = construct!(a, b).group_help_with(move || {
+        counter.set(counter.get() + 1);
+        Doc::from("Takes both A and B")
+    });
+    let parser = ab.to_options();
+    assert_eq!(calls.get(), 0);
+
+    // plain parsing doesn't need the message
+    let r = parser.run_inner(&["-a"]).unwrap();
+    assert_eq!(r, (true, false));
+    assert_eq!(calls.get(), 0);
+
+    let r = parser.run_inner(&["--help"]).unwrap_err().unwrap_stdout();
+    let expected = "\
+Usage: [-a] [-b]
+
+Takes both A and B
+    -a          option a
+    -b          option b
+
+Available options:
+    -h, --help  Prints help information
+";
+    assert_eq!(r, expected);
+    assert!(calls.get() > 0);
+}
+
 #[test]
 fn custom_help_and_version() {
     let h = short('H').long("halp").help("halps you");
@@ -634,3 +670,450 @@ fn help_and_version_newline() {
         .unwrap_stdout();
     assert_eq!(r, "Version: 1\n");
 }
+
+#[test]
+fn version_with_features() {
+    let parser = short('a')
+        .switch()
+        .to_options()
+        .version("1")
+        .version_features(&["color", "tls"]);
+    let r = parser
+        .run_inner(&["--version"])
+        .unwrap_err()
+        .unwrap_stdout();
+    assert_eq!(r, "Version: 1\n\nFeatures: color, tls\n");
+
+    let parser = short('a')
+        .switch()
+        .to_options()
+        .version("1")
+        .version_features(&[]);
+    let r = parser
+        .run_inner(&["--version"])
+        .unwrap_err()
+        .unwrap_stdout();
+    assert_eq!(r, "Version: 1\n\nFeatures: none\n");
+
+    // features alone don't enable --version
+    let parser = short('a').switch().to_options().version_features(&["tls"]);
+    let r = parser
+        .run_inner(&["--version"])
+        .unwrap_err()
+        .unwrap_stderr();
+    assert_eq!(r, "`--version` is not expected in this context");
+}
+
+#[test]
+fn render_usage_only() {
+    let a = short('a').argument::<usize>("A");
+    let b = positional::<String>("B");
+    let parser = construct!(a, b)
+        .to_options()
+        .descr("Description")
+        .name("app");
+    assert_eq!(parser.render_usage(), "Usage: app -a=A B");
+
+    let parser = parser.usage("Usage: app [OPTIONS]");
+    assert_eq!(parser.render_usage(), "Usage: app [OPTIONS]");
+
+    let parser = parser.with_usage(|u| {
+        let mut doc = Doc::default();
+        doc.emphasis("Custom ");
+        doc.doc(&u);
+        doc
+    });
+    assert_eq!(parser.render_usage(), "Custom -a=A B");
+}
+
+#[test]
+fn fail_with_help_in_command() {
+    let a = short('a').help("Use a").req_flag(1);
+    let b = short('b').help("Use b").req_flag(2);
+    let help = fail_with_help();
+    let cmd = construct!([a, b, help])
+        .to_options()
+        .descr("Command description")
+        .command("cmd");
+    let parser = construct!(cmd).to_options();
+
+    let r = parser.run_inner(&["cmd", "-b"]).unwrap();
+    assert_eq!(r, 2);
+
+    let r = parser.run_inner(&["cmd"]).unwrap_err().unwrap_stdout();
+    let expected = "\
+Command description
+
+Usage: cmd (-a | -b)
+
+Available options:
+    -a          Use a
+    -b          Use b
+    -h, --help  Prints help information
+";
+    assert_eq!(r, expected);
+
+    // other errors are reported as usual
+    let r = parser
+        .run_inner(&["cmd", "-a", "-b"])
+        .unwrap_err()
+        .unwrap_stderr();
+    assert_eq!(r, "`-b` cannot be used at the same time as `-a`");
+}
+
+#[test]
+fn fail_with_help_and_fallback() {
+    let a = short('a').argument::<usize>("A");
+    let help = fail_with_help();
+    let parser = construct!([a, help]).fallback(10).to_options();
+
+    let r = parser.run_inner(&[]).unwrap();
+    assert_eq!(r, 10);
+}
+
+#[test]
+fn run_inner_ignores_width_and_color_settings() {
+    let parser = short('v')
+        .help("Print more details about what is being done right now")
+        .switch()
+        .to_options();
+    let plain = parser.run_inner(&["--help"]).unwrap_err().unwrap_stdout();
+
+    let configured = short('v')
+        .help("Print more details about what is being done right now")
+        .switch()
+        .to_options()
+        .max_width(30)
+        .color_help(ColorChoice::Always);
+    let r = configured.run_inner(&["--help"]).unwrap_err();
+    assert_eq!(r.unwrap_stdout(), plain);
+
+    let r = configured.run_inner(&["--help"]).unwrap_err();
+    let narrow = match r {
+        ParseFailure::Stdout(doc, _) => format!("{:30}", doc),
+        _ => unreachable!(),
+    };
+    let expected = "\
+Usage: [-v]
+
+Available options:
+    -v          Print more
+                details about
+                what is being
+                done right now
+    -h, --help  Prints help
+                information
+";
+    assert_eq!(narrow, expected);
+}
+
+#[test]
+fn argument_help_separator() {
+    let a = short('a')
+        .help("space")
+        .argument::<String>("A")
+        .help_separator(HelpSep::Space);
+    let b = short('b')
+        .long("bravo")
+        .help("space")
+        .argument::<String>("B")
+        .help_separator(HelpSep::Space);
+    let c = short('c')
+        .help("adjacent")
+        .argument::<String>("C")
+        .help_separator(HelpSep::Adjacent);
+    let d = long("delta")
+        .help("adjacent")
+        .argument::<String>("D")
+        .help_separator(HelpSep::Adjacent);
+    let e = short('e').help("default").argument::<String>("E");
+    let parser = construct!(a, b, c, d, e).to_options();
+
+    let r = parser.run_inner(&["--help"]).unwrap_err().unwrap_stdout();
+    let expected = "\
+Usage: -a A -b B -cC --delta=D -e=E
+
+Available options:
+    -a A           space
+    -b, --bravo B  space
+    -cC            adjacent
+        --delta=D  adjacent
+    -e=E           default
+    -h, --help     Prints help information
+";
+    assert_eq!(r, expected);
+
+    let r = parser
+        .run_inner(&["-a", "1", "-b=2", "-c3", "--delta", "4", "-e", "5"])
+        .unwrap();
+    assert_eq!(
+        r,
+        (
+            "1".to_owned(),
+            "2".to_owned(),
+            "3".to_owned(),
+            "4".to_owned(),
+            "5".to_owned()
+        )
+    );
+}
+
+#[test]
+fn switch_help_default() {
+    let verbose = short('v')
+        .long("verbose")
+        .help("Be verbose")
+        .switch()
+        .help_default(true);
+    let color = long("no-color")
+        .help("Disable colors")
+        .flag(false, true)
+        .help_default(true);
+    let quiet = short('q').help("Be quiet").switch().help_default(false);
+    let parser = construct!(verbose, color, quiet).to_options();
+
+    let r = parser.run_inner(&["--help"]).unwrap_err().unwrap_stdout();
+    let expected = "\
+Usage: [-v] [--no-color] [-q]
+
+Available options:
+    -v, --verbose   Be verbose
+                    [default: off]
+        --no-color  Disable colors
+                    [default: on]
+    -q              Be quiet
+    -h, --help      Prints help information
+";
+    assert_eq!(r, expected);
+}
+
+#[test]
+fn help_footer_from_commands() {
+    let add = pure(())
+        .to_options()
+        .descr("Add a new item\nItem is added to the end of the list")
+        .command("add");
+    let remove = pure(())
+        .to_options()
+        .descr("Remove an item")
+        .command("remove")
+        .short('r');
+    let hidden = pure(()).to_options().command("hidden").hide();
+    let verbose = short('v').help("Be verbose").switch();
+    let cmd = construct!([add, remove, hidden]);
+    let parser = construct!(verbose, cmd)
+        .to_options()
+        .footer("See the manual for details")
+        .with_help_footer_from_commands("Run `app COMMAND --help` for details on a command:");
+
+    let r = parser.run_inner(&["--help"]).unwrap_err().unwrap_stdout();
+    let expected = "\
+Usage: [-v] COMMAND ...
+
+Available options:
+    -v          Be verbose
+    -h, --help  Prints help information
+
+Available commands:
+    add         Add a new item
+    remove, r   Remove an item
+
+See the manual for details
+
+Run `app COMMAND --help` for details on a command:
+    add         Add a new item
+    remove      Remove an item
+";
+    assert_eq!(r, expected);
+
+    // no commands - no footer
+    let parser = short('v')
+        .help("Be verbose")
+        .switch()
+        .to_options()
+        .with_help_footer_from_commands("unused");
+    let r = parser.run_inner(&["--help"]).unwrap_err().unwrap_stdout();
+    let expected = "\
+Usage: [-v]
+
+Available options:
+    -v          Be verbose
+    -h, --help  Prints help information
+";
+    assert_eq!(r, expected);
+}
+
+#[test]
+fn program_name_overrides_argv0() {
+    let verbose = short('v').help("Be verbose").switch();
+    let name = positional::<String>("NAME");
+    let parser = construct!(verbose, name).to_options().name("mytool");
+
+    let r = parser
+        .run_inner(Args::from(&["--help"]).set_name("cargo-mytool"))
+        .unwrap_err()
+        .unwrap_stdout();
+    let expected = "\
+Usage: mytool [-v] NAME
+
+Available options:
+    -v          Be verbose
+    -h, --help  Prints help information
+";
+    assert_eq!(r, expected);
+
+    let r = parser.run_inner(&[]).unwrap_err().unwrap_stderr();
+    assert_eq!(r, "expected `NAME`, pass `--help` for usage information");
+}
+
+#[test]
+fn help_command_renders_nested_help() {
+    let deploy = short('f')
+        .help("Force deploy")
+        .switch()
+        .to_options()
+        .descr("Deploy the thing")
+        .command("deploy");
+    let build = construct!(deploy)
+        .to_options()
+        .descr("Build the thing")
+        .command("build")
+        .help("Build stuff");
+    let verbose = short('v').help("Be verbose").switch();
+    let parser = construct!(verbose, build).to_options().help_command();
+
+    let r = parser.run_inner(&["help"]).unwrap_err().unwrap_stdout();
+    let expected = "\
+Usage: [-v] COMMAND ...
+
+Available options:
+    -v          Be verbose
+    -h, --help  Prints help information
+
+Available commands:
+    build       Build stuff
+    help        Print help for a command
+";
+    assert_eq!(r, expected);
+
+    let r = parser
+        .run_inner(&["help", "build", "deploy"])
+        .unwrap_err()
+        .unwrap_stdout();
+    let expected = "\
+Deploy the thing
+
+Usage: build deploy [-f]
+
+Available options:
+    -f          Force deploy
+    -h, --help  Prints help information
+";
+    assert_eq!(r, expected);
+
+    let r = parser
+        .run_inner(&["build", "deploy", "--help"])
+        .unwrap_err()
+        .unwrap_stdout();
+    assert_eq!(r, expected);
+
+    let r = parser
+        .run_inner(&["help", "nope"])
+        .unwrap_err()
+        .unwrap_stderr();
+    assert_eq!(r, "`nope` is not expected in this context");
+}
+
+#[test]
+fn split_required_options() {
+    let input = long("input").help("Input file").argument::<String>("FILE");
+    let output = short('o')
+        .long("output")
+        .help("Output file")
+        .argument::<String>("FILE")
+        .fallback("out.txt".to_owned())
+        .display_fallback();
+    let verbose = short('v').help("Be verbose").switch();
+    let fast = long("fast").help("Go fast").req_flag(true);
+    let slow = long("slow").help("Go slow").req_flag(false);
+    let speed = construct!([fast, slow]);
+    let tags = long("tag")
+        .help("Tags to apply")
+        .argument::<String>("TAG")
+        .some("at least one tag is required");
+    let parser = construct!(input, output, verbose, speed, tags).to_options();
+
+    let r = parser.run_inner(&["--help"]).unwrap_err().unwrap_stdout();
+    let expected = "\
+Usage: --input=FILE [-o=FILE] [-v] (--fast | --slow) --tag=TAG...
+
+Available options:
+        --input=FILE   Input file
+    -o, --output=FILE  Output file
+                       [default: out.txt]
+    -v                 Be verbose
+        --fast         Go fast
+        --slow         Go slow
+        --tag=TAG      Tags to apply
+    -h, --help         Prints help information
+";
+    assert_eq!(r, expected);
+
+    let parser = parser.split_required(true);
+    let r = parser.run_inner(&["--help"]).unwrap_err().unwrap_stdout();
+    let expected = "\
+Usage: --input=FILE [-o=FILE] [-v] (--fast | --slow) --tag=TAG...
+
+Required options:
+        --input=FILE   Input file
+        --tag=TAG      Tags to apply
+
+Available options:
+    -o, --output=FILE  Output file
+                       [default: out.txt]
+    -v                 Be verbose
+        --fast         Go fast
+        --slow         Go slow
+    -h, --help         Prints help information
+";
+    assert_eq!(r, expected);
+}
+
+#[test]
+fn usage_examples_section() {
+    let release = long("release").help("Build in release mode").switch();
+    let build = construct!(release)
+        .to_options()
+        .descr("Build the project")
+        .command("build");
+    let parser = build
+        .to_options()
+        .example("mytool build --release", "Build an optimized binary")
+        .example("mytool build", "Build a debug binary")
+        .footer("See the manual for details");
+
+    let r = parser.run_inner(&["--help"]).unwrap_err().unwrap_stdout();
+    let expected = "\
+Usage: COMMAND ...
+
+Available options:
+    -h, --help              Prints help information
+
+Available commands:
+    build                   Build the project
+
+Examples:
+    mytool build --release  Build an optimized binary
+    mytool build            Build a debug binary
+
+See the manual for details
+";
+    assert_eq!(r, expected);
+
+    // examples belong to the parser they are added to
+    let r = parser
+        .run_inner(&["build", "--help"])
+        .unwrap_err()
+        .unwrap_stdout();
+    assert!(!r.contains("Examples:"));
+}
@@ -58,3 +58,99 @@ fn fixed_adjacent_is_not_ok() {
     let c = short('c').switch();
     construct!(ab, c).to_options().check_invariants(false);
 }
+
+#[test]
+fn adjacent_inside_command_is_ok() {
+    let a = short('a').req_flag(());
+    let b = short('b').switch();
+    let c = positional::<String>("C");
+    construct!(a, b, c)
+        .adjacent()
+        .many()
+        .to_options()
+        .command("cmd")
+        .to_options()
+        .check_invariants(false);
+}
+
+#[should_panic(expected = "bpaf usage BUG: adjacent block must start with")]
+#[test]
+fn adjacent_starting_with_pure() {
+    let a = pure(());
+    let b = positional::<String>("B");
+    let ab = construct!(a, b).adjacent();
+    let c = short('c').switch();
+    construct!(c, ab).to_options().check_invariants(false);
+}
+
+#[test]
+fn adjacent_starting_with_alternatives() {
+    let a = short('a').req_flag(1);
+    let b = short('b').argument::<usize>("B");
+    let ab = construct!([a, b]);
+    let c = positional::<String>("C");
+    let parser = construct!(ab, c).adjacent().to_options();
+    parser.check_invariants(false);
+
+    let r = parser.run_inner(&["-a", "x"]).unwrap();
+    assert_eq!(r, (1, "x".to_owned()));
+
+    let r = parser.run_inner(&["-b", "2", "x"]).unwrap();
+    assert_eq!(r, (2, "x".to_owned()));
+
+    let r = parser.run_inner(&["x"]).unwrap_err().unwrap_stderr();
+    assert_eq!(
+        r,
+        "expected `-a` or `-b=B`, got `x`. Pass `--help` for usage information"
+    );
+}
+
+#[should_panic(expected = "bpaf usage BUG: adjacent block is located by its first item")]
+#[test]
+fn adjacent_starting_with_optional_alternative() {
+    let a = short('a').req_flag(1);
+    let b = short('b').argument::<usize>("B").optional().map(|_| 2);
+    let ab = construct!([a, b]);
+    let c = positional::<String>("C");
+    construct!(ab, c)
+        .adjacent()
+        .to_options()
+        .check_invariants(false);
+}
+
+#[test]
+fn adjacent_starting_with_switch() {
+    let s = short('s').switch();
+    let c = short('c').argument::<String>("C");
+    let parser = construct!(s, c).adjacent().many().to_options();
+    parser.check_invariants(false);
+
+    let r = parser
+        .run_inner(&["-s", "-c", "x", "-s", "-c", "y"])
+        .unwrap();
+    assert_eq!(r, vec![(true, "x".to_owned()), (true, "y".to_owned())]);
+}
+
+#[should_panic(expected = "bpaf usage BUG: adjacent block is located by its first item")]
+#[test]
+fn adjacent_starting_with_optional() {
+    let a = short('a').argument::<usize>("A").optional();
+    let b = positional::<String>("B");
+    construct!(a, b)
+        .adjacent()
+        .to_options()
+        .check_invariants(false);
+}
+
+#[should_panic(expected = "bpaf usage BUG: adjacent block is located by its first item")]
+#[test]
+fn adjacent_starting_with_optional_nested() {
+    let a = short('a').argument::<usize>("A").optional();
+    let b = positional::<String>("B");
+    let ab = construct!(a, b).adjacent();
+    construct!(ab)
+        .to_options()
+        .command("cmd")
+        .to_options()
+        .check_invariants(false);
+}
@@ -51,6 +51,30 @@ fn simple() {
     assert!(write_updated(&roff, "tests/simple.1").unwrap());
 }
 
+#[test]
+fn examples() {
+    let kraken = short('d')
+        .long("kraken")
+        .help("Unleash the kraken")
+        .switch();
+
+    let options = kraken
+        .to_options()
+        .example("simple -d", "Unleash the kraken")
+        .footer("Beware `-d`, dragons be here");
+    let roff = options.render_manpage("simple", Section::General, None, None, None);
+
+    let expected = "\
+.SS EXAMPLES:
+.TP
+\\fBsimple \\-d\\fP
+\\fRUnleash the kraken\\fP
+.PP
+.PP
+\\fRBeware `\\-d`, dragons be here\\fP";
+    assert!(roff.ends_with(expected), "{}", roff);
+}
+
 #[test]
 fn nested() {
     let a = short('d')
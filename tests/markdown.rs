@@ -254,3 +254,31 @@ Available options:
 
     assert_eq!(r, expected);
 }
+
+#[test]
+fn usage_examples() {
+    let opts = short('a')
+        .help("help")
+        .switch()
+        .to_options()
+        .example("ml -a", "Run with `-a`");
+    let r = opts.render_markdown("ml");
+
+    let expected = "\
+# ml
+
+**Usage**: **`ml`** \\[**`-a`**\\]
+
+**Available options:**
+- **`-a`** &mdash; \n  help
+- **`-h`**, **`--help`** &mdash; \n  Prints help information
+
+
+
+**Examples:**
+- **`ml -a`** &mdash; \n  Run with `-a`
+
+
+";
+    assert_eq!(r, expected);
+}
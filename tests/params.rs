@@ -115,3 +115,339 @@ fn command_alias() {
     groups().run_inner(&["top"]).unwrap();
     groups().run_inner(&["top-alias"]).unwrap();
 }
+
+#[test]
+fn rest_as_string() {
+    let script = short('c').long("cmd").rest_as_string("SCRIPT");
+    let verbose = short('v').switch();
+    let size = short('s').argument::<usize>("SIZE").optional();
+    let parser = construct!(script, verbose, size).to_options();
+
+    let r = parser.run_inner(&["-c", "echo", "hi"]).unwrap();
+    assert_eq!(r, ("echo hi".to_owned(), false, None));
+
+    // flags after are part of the value, flags before are parsed as usual
+    let r = parser
+        .run_inner(&["-s", "3", "--cmd", "ls", "-v", "-s", "4"])
+        .unwrap();
+    assert_eq!(r, ("ls -v -s 4".to_owned(), false, Some(3)));
+
+    // words split by bpaf are joined back as typed
+    let r = parser
+        .run_inner(&["-c", "x", "-s4", "--long=val", "-vs=5"])
+        .unwrap();
+    assert_eq!(r, ("x -s4 --long=val -vs=5".to_owned(), false, None));
+
+    // value can start in the same word
+    let r = parser.run_inner(&["-v", "-c=ls", "-la"]).unwrap();
+    assert_eq!(r, ("ls -la".to_owned(), true, None));
+
+    // double dash before the value is consumed, once value started it is kept
+    let r = parser.run_inner(&["-c", "--", "ls", "-la"]).unwrap();
+    assert_eq!(r, ("ls -la".to_owned(), false, None));
+
+    let r = parser
+        .run_inner(&["-c", "git", "log", "--", "file"])
+        .unwrap();
+    assert_eq!(r, ("git log -- file".to_owned(), false, None));
+
+    let r = parser.run_inner(&["-v", "-c=ls", "--", "-la"]).unwrap();
+    assert_eq!(r, ("ls -- -la".to_owned(), true, None));
+
+    let r = parser
+        .run_inner(&["-c", "a", "--", "b", "--", "c"])
+        .unwrap();
+    assert_eq!(r, ("a -- b -- c".to_owned(), false, None));
+
+    let r = parser.run_inner(&["-v", "-c"]).unwrap_err().unwrap_stderr();
+    assert_eq!(r, "`-c` requires an argument `SCRIPT`");
+
+    let r = parser.run_inner(&["-v"]).unwrap_err().unwrap_stderr();
+    assert_eq!(
+        r,
+        "expected `--cmd=SCRIPT`, pass `--help` for usage information"
+    );
+}
+
+#[test]
+fn argument_or_stdin() {
+    let input = short('i').long("input").argument_or_stdin("FILE");
+    let parser = input.to_options();
+
+    let r = parser.run_inner(&["-i", "-"]).unwrap();
+    assert_eq!(r, FileOrStdin::Stdin);
+
+    let r = parser.run_inner(&["--input=-"]).unwrap();
+    assert_eq!(r, FileOrStdin::Stdin);
+
+    let r = parser.run_inner(&["--input", "a.txt"]).unwrap();
+    assert_eq!(r, FileOrStdin::File("a.txt".into()));
+
+    let r = parser.run_inner(&["--input"]).unwrap_err().unwrap_stderr();
+    assert_eq!(r, "`--input` requires an argument `FILE`");
+
+    let r = parser.run_inner(&["--help"]).unwrap_err().unwrap_stdout();
+    let expected = "\
+Usage: -i=FILE
+
+Available options:
+    -i, --input=FILE
+    -h, --help        Prints help information
+";
+    assert_eq!(r, expected);
+}
+
+#[test]
+fn argument_range_bounds() {
+    let level = long("level")
+        .help("Number of levels")
+        .argument_range::<u32>("LEVEL", 2..=16);
+    let parser = level.to_options();
+
+    let r = parser.run_inner(&["--level", "2"]).unwrap();
+    assert_eq!(r, 2);
+
+    let r = parser.run_inner(&["--level=16"]).unwrap();
+    assert_eq!(r, 16);
+
+    let r = parser
+        .run_inner(&["--level", "1"])
+        .unwrap_err()
+        .unwrap_stderr();
+    assert_eq!(r, "couldn't parse `1`: LEVEL must be in 2..=16");
+
+    let r = parser
+        .run_inner(&["--level", "17"])
+        .unwrap_err()
+        .unwrap_stderr();
+    assert_eq!(r, "couldn't parse `17`: LEVEL must be in 2..=16");
+
+    let r = parser
+        .run_inner(&["--level", "many"])
+        .unwrap_err()
+        .unwrap_stderr();
+    assert_eq!(r, "couldn't parse `many`: invalid digit found in string");
+
+    let r = parser.run_inner(&["--help"]).unwrap_err().unwrap_stdout();
+    let expected = "\
+Usage: --level=2-16
+
+Available options:
+        --level=2-16  Number of levels
+    -h, --help        Prints help information
+";
+    assert_eq!(r, expected);
+}
+
+#[test]
+fn argument_range_negative() {
+    let offset = long("offset")
+        .help("Offset from the end")
+        .argument_range::<i32>("N", -5..=-1);
+    let parser = offset.to_options();
+
+    let r = parser.run_inner(&["--offset=-3"]).unwrap();
+    assert_eq!(r, -3);
+
+    let r = parser
+        .run_inner(&["--offset=0"])
+        .unwrap_err()
+        .unwrap_stderr();
+    assert_eq!(r, "couldn't parse `0`: N must be in -5..=-1");
+
+    let r = parser.run_inner(&["--help"]).unwrap_err().unwrap_stdout();
+    let expected = "\
+Usage: --offset=<-5..=-1>
+
+Available options:
+        --offset=<-5..=-1>  Offset from the end
+    -h, --help              Prints help information
+";
+    assert_eq!(r, expected);
+}
+
+#[test]
+fn collect_layered_overrides() {
+    let set = long("set")
+        .help("Override a value")
+        .collect_layered::<String, u32>("KEY=VALUE");
+    let verbose = short('v').switch();
+    let parser = construct!(set, verbose).to_options();
+
+    let r = parser.run_inner(&[]).unwrap();
+    assert_eq!(r, (Vec::new(), false));
+
+    let r = parser
+        .run_inner(&["--set", "a=1", "-v", "--set", "b=2", "--set", "a=3"])
+        .unwrap();
+    assert_eq!(
+        r.0,
+        vec![
+            ("a".to_owned(), 1, 1),
+            ("b".to_owned(), 2, 4),
+            ("a".to_owned(), 3, 6),
+        ]
+    );
+
+    // attached values take two items, positions are still ordered
+    let r = parser.run_inner(&["--set=a=1", "--set", "a=2"]).unwrap();
+    assert_eq!(r.0, vec![("a".to_owned(), 1, 1), ("a".to_owned(), 2, 3)]);
+
+    let r = parser
+        .run_inner(&["--set", "a"])
+        .unwrap_err()
+        .unwrap_stderr();
+    assert_eq!(
+        r,
+        "couldn't parse `a`: expected `=` between a key and a value"
+    );
+
+    let r = parser
+        .run_inner(&["--set", "a=x"])
+        .unwrap_err()
+        .unwrap_stderr();
+    assert_eq!(r, "couldn't parse `a=x`: invalid digit found in string");
+
+    let r = parser.run_inner(&["--help"]).unwrap_err().unwrap_stdout();
+    let expected = "\
+Usage: [--set=<KEY=VALUE>]... [-v]
+
+Available options:
+        --set=<KEY=VALUE>  Override a value
+    -v
+    -h, --help             Prints help information
+";
+    assert_eq!(r, expected);
+}
+
+#[test]
+fn value_spec_shared() {
+    fn level(input: &u8) -> Vec<(String, Option<String>)> {
+        vec![(input.to_string(), Some("Level".to_owned()))]
+    }
+    let spec = ValueSpec::<u8>::new("LEVEL")
+        .help("Compression level")
+        .guard(|l| *l <= 9, "level must be at most 9")
+        .complete(level);
+
+    let named = short('l').value(spec.clone());
+    let fast = long("fast").help("Fast level").value(spec.clone());
+    let pos = positional_spec(spec);
+    let parser = construct!(named, fast, pos).to_options();
+
+    let r = parser.run_inner(&["-l", "1", "--fast", "2", "3"]).unwrap();
+    assert_eq!(r, (1, 2, 3));
+
+    let r = parser
+        .run_inner(&["-l", "1", "--fast", "2", "10"])
+        .unwrap_err()
+        .unwrap_stderr();
+    assert_eq!(r, "`10`: level must be at most 9");
+
+    let r = parser
+        .run_inner(&["-l", "x", "--fast", "2", "3"])
+        .unwrap_err()
+        .unwrap_stderr();
+    assert_eq!(r, "couldn't parse `x`: invalid digit found in string");
+
+    let r = parser.run_inner(&["--help"]).unwrap_err().unwrap_stdout();
+    let expected = "\
+Usage: -l=LEVEL --fast=LEVEL LEVEL
+
+Available positional items:
+    LEVEL             Compression level
+
+Available options:
+    -l=LEVEL          Compression level
+        --fast=LEVEL  Fast level
+    -h, --help        Prints help information
+";
+    assert_eq!(r, expected);
+
+    let r = parser
+        .run_inner(Args::from(&["-l", "1", "--fast", "2", "4"]).set_comp(0))
+        .unwrap_err()
+        .unwrap_stdout();
+    assert_eq!(r, "4");
+}
+
+#[test]
+fn argument_parsed_by_nested() {
+    let verbose = short('v').switch();
+    let words = positional::<String>("WORD").many();
+    let inner = construct!(verbose, words).to_options();
+    let run = short('r')
+        .long("run")
+        .help("Command to run")
+        .argument_parsed_by("CMD", inner);
+    let dry = long("dry").switch();
+    let parser = construct!(run, dry).to_options();
+
+    let r = parser
+        .run_inner(&["-r", r#"a  'b c'\ d "e \"f\" \n" g''h '' "#, "--dry"])
+        .unwrap();
+    assert_eq!(
+        r,
+        (
+            (
+                false,
+                vec![
+                    "a".to_owned(),
+                    "b c d".to_owned(),
+                    r#"e "f" \n"#.to_owned(),
+                    "gh".to_owned(),
+                    String::new(),
+                ]
+            ),
+            true
+        )
+    );
+
+    let r = parser.run_inner(&["--run=-v x"]).unwrap();
+    assert_eq!(r, ((true, vec!["x".to_owned()]), false));
+
+    let r = parser
+        .run_inner(&["--run", "a 'b"])
+        .unwrap_err()
+        .unwrap_stderr();
+    assert_eq!(r, "couldn't parse `a 'b`: unterminated `'` quote");
+
+    let r = parser.run_inner(&["-r=-x"]).unwrap_err().unwrap_stderr();
+    assert_eq!(r, "-r: `-x` is not expected in this context");
+
+    let r = parser
+        .run_inner(&["--run=--help"])
+        .unwrap_err()
+        .unwrap_stdout();
+    let expected = "\
+Usage: [-v] [WORD]...
+
+Available options:
+    -v
+    -h, --help  Prints help information
+";
+    assert_eq!(r, expected);
+}
+
+#[test]
+fn argument_parsed_by_env() {
+    let verbose = short('v').switch();
+    let inner = verbose.to_options();
+    let run = long("run")
+        .env("BPAF_TEST_RUN_CMD")
+        .argument_parsed_by("CMD", inner);
+    let parser = run.to_options();
+
+    std::env::set_var("BPAF_TEST_RUN_CMD", "-v");
+    let r = parser.run_inner(&[]).unwrap();
+    assert!(r);
+
+    std::env::set_var("BPAF_TEST_RUN_CMD", "-x");
+    let r = parser.run_inner(&[]).unwrap_err().unwrap_stderr();
+    assert_eq!(r, "BPAF_TEST_RUN_CMD: `-x` is not expected in this context");
+
+    // command line still takes priority
+    let r = parser.run_inner(&["--run=-q"]).unwrap_err().unwrap_stderr();
+    assert_eq!(r, "--run: `-q` is not expected in this context");
+    std::env::remove_var("BPAF_TEST_RUN_CMD");
+}
@@ -158,3 +158,96 @@ fn non_strictly_positional() {
     let r = parser.run_inner(&["--"]).unwrap_err().unwrap_stderr();
     assert_eq!(r, "expected `A`, pass `--help` for usage information");
 }
+
+#[test]
+fn positional_with_several_interpretations() {
+    #[derive(Debug, Clone, PartialEq)]
+    enum Target {
+        Port(u16),
+        Name(String),
+    }
+    let verbose = short('v').switch();
+    let port = positional::<u16>("TARGET").map(Target::Port);
+    let name = positional::<String>("TARGET").map(Target::Name);
+    let target = construct!([port, name]);
+    let extra = positional::<String>("EXTRA").optional();
+    let parser = construct!(verbose, target, extra).to_options();
+
+    let r = parser.run_inner(&["80"]).unwrap();
+    assert_eq!(r, (false, Target::Port(80), None));
+
+    // value is consumed once, by the first interpretation that succeeds
+    let r = parser.run_inner(&["-v", "80", "90"]).unwrap();
+    assert_eq!(r, (true, Target::Port(80), Some("90".to_owned())));
+
+    let r = parser.run_inner(&["example.com", "80"]).unwrap();
+    assert_eq!(
+        r,
+        (
+            false,
+            Target::Name("example.com".to_owned()),
+            Some("80".to_owned())
+        )
+    );
+
+    let r = parser.run_inner(&[]).unwrap_err().unwrap_stderr();
+    assert_eq!(r, "expected `TARGET`, pass `--help` for usage information");
+
+    let r = parser.run_inner(&["--help"]).unwrap_err().unwrap_stdout();
+    let expected = "\
+Usage: [-v] TARGET [EXTRA]
+
+Available options:
+    -v
+    -h, --help  Prints help information
+";
+    assert_eq!(r, expected);
+
+    // when no interpretations succeed the first error is reported
+    let port = positional::<u16>("PORT").map(Target::Port);
+    let ip = positional::<std::net::IpAddr>("PORT").map(|ip| Target::Name(ip.to_string()));
+    let parser = construct!([port, ip]).to_options();
+    let r = parser.run_inner(&["http"]).unwrap_err().unwrap_stderr();
+    assert_eq!(r, "couldn't parse `http`: invalid digit found in string");
+}
+
+#[test]
+fn positional_stream_converts_lazily() {
+    use std::path::PathBuf;
+
+    let verbose = short('v').switch();
+    let files = positional_stream::<PathBuf>("FILE").help("Files to process");
+    let parser = construct!(verbose, files).to_options();
+
+    let (verbose, files) = parser.run_inner(&["a", "-v", "--", "-b"]).unwrap();
+    assert!(verbose);
+    let r = files.collect::<Result<Vec<_>, _>>().unwrap();
+    assert_eq!(r, [PathBuf::from("a"), PathBuf::from("-b")]);
+
+    let (_, files) = parser.run_inner(&[]).unwrap();
+    assert_eq!(files.count(), 0);
+
+    let r = parser.run_inner(&["--help"]).unwrap_err().unwrap_stdout();
+    let expected = "\
+Usage: [-v] [FILE]...
+
+Available positional items:
+    FILE        Files to process
+
+Available options:
+    -v
+    -h, --help  Prints help information
+";
+    assert_eq!(r, expected);
+
+    let numbers = positional_stream::<u8>("N").to_options();
+    let mut iter = numbers.run_inner(&["1", "300", "x"]).unwrap();
+    assert_eq!(iter.next(), Some(Ok(1)));
+    assert_eq!(
+        iter.next(),
+        Some(Err(
+            "couldn't parse `300`: number too large to fit in target type".to_owned()
+        ))
+    );
+    assert_eq!(iter.next(), None);
+}
@@ -303,6 +303,37 @@ fn parse_errors() {
     assert_eq!(expected_err, err);
 }
 
+#[test]
+fn parse_exact_errors() {
+    fn positive(x: i32) -> Result<i32, String> {
+        if x > 0 {
+            Ok(x)
+        } else {
+            Err(format!("{} is not positive", x))
+        }
+    }
+
+    let a = short('a').argument::<i32>("A").parse(positive);
+    let b = short('b')
+        .argument::<i32>("B")
+        .parse_exact(positive)
+        .optional();
+    let parser = construct!(a, b).to_options();
+
+    let r = parser.run_inner(&["-a", "1", "-b", "2"]).unwrap();
+    assert_eq!(r, (1, Some(2)));
+
+    let err = parser.run_inner(&["-a", "0"]).unwrap_err().unwrap_stderr();
+    assert_eq!(err, "couldn't parse `0`: 0 is not positive");
+
+    // not recovered by optional, same as parse
+    let err = parser
+        .run_inner(&["-a", "1", "-b=-3"])
+        .unwrap_err()
+        .unwrap_stderr();
+    assert_eq!(err, "-3 is not positive");
+}
+
 #[test]
 #[ignore]
 fn custom_usage() {
@@ -1827,3 +1858,548 @@ fn flag_like_commands() {
         "Usage: --add -a\n\nAvailable options:\n    -a\n    -h, --help  Prints help information\n";
     assert_eq!(r, expected);
 }
+
+#[test]
+fn option_parser_then() {
+    let a = short('a').argument::<u32>("A").fallback(10);
+    let parser = a.to_options().then(|a| {
+        if a % 2 == 0 {
+            Ok(a / 2)
+        } else {
+            Err(format!("{} is odd", a))
+        }
+    });
+
+    let r = parser.run_inner(&[]).unwrap();
+    assert_eq!(r, 5);
+
+    let r = parser.run_inner(&["-a", "7"]).unwrap_err().unwrap_stderr();
+    assert_eq!(r, "7 is odd");
+
+    // help and unexpected items are not affected by failing post processing
+    let r = parser.run_inner(&["-a", "7", "--help"]).unwrap_err();
+    assert!(matches!(r, ParseFailure::Stdout(..)));
+
+    let r = parser
+        .run_inner(&["-a", "7", "-b"])
+        .unwrap_err()
+        .unwrap_stderr();
+    assert_eq!(r, "`-b` is not expected in this context");
+
+    // works for subcommands too
+    let cmd = parser.command("cmd");
+    let parser = construct!(cmd).to_options();
+    let r = parser.run_inner(&["cmd", "-a", "4"]).unwrap();
+    assert_eq!(r, 2);
+
+    let r = parser
+        .run_inner(&["cmd", "-a", "3"])
+        .unwrap_err()
+        .unwrap_stderr();
+    assert_eq!(r, "3 is odd");
+}
+
+#[test]
+fn default_command_on_bare_invocation() {
+    #[derive(Debug, Clone, PartialEq)]
+    enum Cmd {
+        Status(bool),
+        Log(usize),
+    }
+
+    let brief = short('s').switch().map(Cmd::Status);
+    let status = brief.to_options().descr("show status").command("status");
+    let count = short('n').argument::<usize>("N").fallback(10).map(Cmd::Log);
+    let log = count
+        .to_options()
+        .descr("show log")
+        .command("log")
+        .short('l');
+    let verbose = short('v').switch();
+    let cmd = construct!([status, log]);
+    let parser = construct!(verbose, cmd)
+        .to_options()
+        .default_command("status");
+
+    let r = parser.run_inner(&[]).unwrap();
+    assert_eq!(r, (false, Cmd::Status(false)));
+
+    let r = parser.run_inner(&["-v"]).unwrap();
+    assert_eq!(r, (true, Cmd::Status(false)));
+
+    let r = parser.run_inner(&["-v", "-s"]).unwrap();
+    assert_eq!(r, (true, Cmd::Status(true)));
+
+    let r = parser.run_inner(&["status", "-s"]).unwrap();
+    assert_eq!(r, (false, Cmd::Status(true)));
+
+    let r = parser.run_inner(&["log", "-n", "3"]).unwrap();
+    assert_eq!(r, (false, Cmd::Log(3)));
+
+    let r = parser.run_inner(&["-v", "l"]).unwrap();
+    assert_eq!(r, (true, Cmd::Log(10)));
+
+    // items the default command can't handle are reported from its context
+    let r = parser.run_inner(&["-n", "3"]).unwrap_err().unwrap_stderr();
+    assert_eq!(r, "`-n` is not expected in this context");
+
+    // help is still about the parent parser
+    let r = parser.run_inner(&["--help"]).unwrap_err().unwrap_stdout();
+    let expected = "\
+Usage: [-v] COMMAND ...
+
+Available options:
+    -v
+    -h, --help  Prints help information
+
+Available commands:
+    status      show status
+    log, l      show log
+";
+    assert_eq!(r, expected);
+
+    // fallback_to_usage takes priority for bare invocation only
+    let parser = parser.fallback_to_usage();
+    let r = parser.run_inner(&[]).unwrap_err().unwrap_stdout();
+    assert_eq!(r, expected);
+
+    let r = parser.run_inner(&["-s"]).unwrap();
+    assert_eq!(r, (false, Cmd::Status(true)));
+}
+
+#[test]
+fn default_command_with_argument_value() {
+    #[derive(Debug, Clone, PartialEq)]
+    enum Cmd {
+        Status,
+        Build,
+    }
+
+    let status = pure(Cmd::Status).to_options().command("status");
+    let build = pure(Cmd::Build).to_options().command("build");
+    let name = long("name").argument::<String>("NAME");
+    let cmd = construct!([status, build]);
+    let parser = construct!(name, cmd).to_options().default_command("status");
+
+    // value of --name is not a command
+    let r = parser.run_inner(&["--name", "build"]).unwrap();
+    assert_eq!(r, ("build".to_owned(), Cmd::Status));
+
+    let r = parser.run_inner(&["--name=build"]).unwrap();
+    assert_eq!(r, ("build".to_owned(), Cmd::Status));
+
+    let r = parser.run_inner(&["--name", "x", "build"]).unwrap();
+    assert_eq!(r, ("x".to_owned(), Cmd::Build));
+}
+
+#[test]
+#[should_panic(expected = "default_command \"stats\" must be a command")]
+fn default_command_must_exist() {
+    let status = pure(()).to_options().command("status");
+    let _ = status.to_options().default_command("stats");
+}
+
+#[test]
+fn global_flag_around_commands() {
+    let release = long("release").help("Build in release mode").switch();
+    let target = long("target")
+        .help("Target to build")
+        .argument::<String>("T");
+    let docs = construct!(target)
+        .to_options()
+        .descr("Build docs")
+        .command("docs");
+    let build = construct!(release, docs)
+        .to_options()
+        .descr("Build things")
+        .command("build");
+    let verbose = short('v')
+        .long("verbose")
+        .help("Print more")
+        .switch()
+        .global();
+    let parser = construct!(verbose, build).to_options();
+
+    let r = parser
+        .run_inner(&["-v", "build", "docs", "--target", "x"])
+        .unwrap();
+    assert_eq!(r, (true, (false, "x".to_owned())));
+
+    let r = parser
+        .run_inner(&["build", "-v", "docs", "--target", "x"])
+        .unwrap();
+    assert_eq!(r, (true, (false, "x".to_owned())));
+
+    let r = parser
+        .run_inner(&["build", "--release", "docs", "--target", "x", "--verbose"])
+        .unwrap();
+    assert_eq!(r, (true, (true, "x".to_owned())));
+
+    let r = parser
+        .run_inner(&["build", "docs", "--target", "x"])
+        .unwrap();
+    assert_eq!(r, (false, (false, "x".to_owned())));
+
+    // global items are documented in the nested commands
+    let r = parser
+        .run_inner(&["build", "docs", "--help"])
+        .unwrap_err()
+        .unwrap_stdout();
+    let expected = "\
+Build docs
+
+Usage: build docs --target=T
+
+Global options:
+    -v, --verbose   Print more
+
+Available options:
+        --target=T  Target to build
+    -h, --help      Prints help information
+";
+    assert_eq!(r, expected);
+
+    // but not twice in the parser that defines them
+    let r = parser.run_inner(&["--help"]).unwrap_err().unwrap_stdout();
+    let expected = "\
+Usage: [-v] COMMAND ...
+
+Available options:
+    -v, --verbose  Print more
+    -h, --help     Prints help information
+
+Available commands:
+    build          Build things
+";
+    assert_eq!(r, expected);
+}
+
+#[test]
+fn prepended_args_have_lower_priority() {
+    use std::ffi::OsString;
+    let level = long("level").argument::<u32>("L").last();
+    let name = long("name").argument::<String>("NAME").optional();
+    let parser = construct!(level, name)
+        .to_options()
+        .prepend_args(&[OsString::from("--level=1"), OsString::from("--name=bob")]);
+
+    let r = parser.run_inner(&[]).unwrap();
+    assert_eq!(r, (1, Some("bob".to_owned())));
+
+    let r = parser.run_inner(&["--level", "3"]).unwrap();
+    assert_eq!(r, (3, Some("bob".to_owned())));
+
+    // single value parsers don't allow overriding
+    let r = parser
+        .run_inner(&["--name", "alice"])
+        .unwrap_err()
+        .unwrap_stderr();
+    assert_eq!(
+        r,
+        "argument `--name` cannot be used multiple times in this context"
+    );
+}
+
+#[test]
+fn prepended_args_and_double_dash() {
+    use std::ffi::OsString;
+    fn parser() -> OptionParser<(u32, Vec<String>)> {
+        let level = long("level").argument::<u32>("L").last();
+        let files = positional::<String>("FILE").many();
+        construct!(level, files).to_options()
+    }
+
+    // user's own double dash works the same with or without prepended items
+    let with_level = parser().prepend_args(&[OsString::from("--level=1")]);
+    let r = with_level.run_inner(&["--", "--level=2"]).unwrap();
+    assert_eq!(r, (1, vec!["--level=2".to_owned()]));
+    let r = with_level.run_inner(&["--level=2", "--", "-x"]).unwrap();
+    assert_eq!(r, (2, vec!["-x".to_owned()]));
+
+    // double dash in prepended items doesn't make user's items positional
+    let with_file = parser().prepend_args(&[
+        OsString::from("--level=1"),
+        OsString::from("--"),
+        OsString::from("-a"),
+    ]);
+    let r = with_file.run_inner(&["--level=2", "b"]).unwrap();
+    assert_eq!(r, (2, vec!["-a".to_owned(), "b".to_owned()]));
+    let r = with_file.run_inner(&["--", "--level=3"]).unwrap();
+    assert_eq!(r, (1, vec!["-a".to_owned(), "--level=3".to_owned()]));
+}
+
+#[test]
+fn count_to_enum_saturates() {
+    let verbose = short('v')
+        .help("Be verbose")
+        .req_flag(())
+        .count()
+        .count_to_enum(["info", "debug"], "warn");
+    let parser = verbose.to_options();
+
+    let r = parser.run_inner(&[]).unwrap();
+    assert_eq!(r, "warn");
+    let r = parser.run_inner(&["-v"]).unwrap();
+    assert_eq!(r, "info");
+    let r = parser.run_inner(&["-v", "-v"]).unwrap();
+    assert_eq!(r, "debug");
+    let r = parser.run_inner(&["-vvvv"]).unwrap();
+    assert_eq!(r, "debug");
+
+    let r = parser.run_inner(&["--help"]).unwrap_err().unwrap_stdout();
+    let expected = "\
+Usage: [-v]...
+
+Available options:
+    -v          Be verbose
+    -h, --help  Prints help information
+";
+    assert_eq!(r, expected);
+
+    let none = short('q')
+        .req_flag(())
+        .count()
+        .count_to_enum::<_, 0>([], "same");
+    let parser = none.to_options();
+    let r = parser.run_inner(&["-qq"]).unwrap();
+    assert_eq!(r, "same");
+}
+
+#[test]
+fn memoize_reuses_value_within_single_run() {
+    use std::{cell::Cell, rc::Rc};
+
+    // evaluates the inner parser on a copy of the input first, the way alternatives do
+    struct Twice<P>(P);
+    impl<T, P: Parser<T>> Parser<T> for Twice<P> {
+        fn eval(&self, args: &mut State) -> Result<T, Error> {
+            let _ = self.0.eval(&mut args.clone());
+            self.0.eval(args)
+        }
+
+        fn meta(&self) -> Meta {
+            self.0.meta()
+        }
+    }
+
+    let calls = Rc::new(Cell::new(0));
+    let c = calls.clone();
+    let config = long("config")
+        .argument::<String>("FILE")
+        .parse(move |name| {
+            c.set(c.get() + 1);
+            Ok::<_, String>(name.len())
+        })
+        .memoize();
+    let config = Twice(config);
+    let verbose = short('v').switch();
+    let parser = construct!(config, verbose).to_options();
+
+    let r = parser.run_inner(&["--config", "app.toml", "-v"]).unwrap();
+    assert_eq!(r, (8, true));
+    assert_eq!(calls.get(), 1);
+
+    // cached value is replayed together with consumed items so leftovers are still reported
+    let r = parser
+        .run_inner(&["--config", "app.toml", "extra"])
+        .unwrap_err()
+        .unwrap_stderr();
+    assert_eq!(r, "`extra` is not expected in this context");
+    assert_eq!(calls.get(), 2);
+
+    let r = parser.run_inner(&["--config", "x.toml"]).unwrap();
+    assert_eq!(r, (6, false));
+    assert_eq!(calls.get(), 3);
+}
+
+#[test]
+fn getopt_style_short_block_with_argument() {
+    let x = short('x').switch();
+    let v = short('v').switch();
+    let f = short('f').argument::<String>("FILE");
+    let parser = construct!(x, v, f).to_options();
+
+    let r = parser.run_inner(&["-xvf", "value"]).unwrap();
+    assert_eq!(r, (true, true, "value".to_owned()));
+
+    let r = parser.run_inner(&["-vxfvalue"]).unwrap();
+    assert_eq!(r, (true, true, "value".to_owned()));
+
+    let r = parser.run_inner(&["-xvf=value"]).unwrap();
+    assert_eq!(r, (true, true, "value".to_owned()));
+
+    let r = parser
+        .run_inner(&["-xvf=", "-v"])
+        .unwrap_err()
+        .unwrap_stderr();
+    assert_eq!(
+        r,
+        "argument `-v` cannot be used multiple times in this context"
+    );
+
+    let r = parser.run_inner(&["-xf", "-"]).unwrap();
+    assert_eq!(r, (true, false, "-".to_owned()));
+
+    // everything past the argument name is the value
+    let r = parser
+        .run_inner(&["-xfv", "value"])
+        .unwrap_err()
+        .unwrap_stderr();
+    assert_eq!(r, "`value` is not expected in this context");
+
+    let r = parser.run_inner(&["-xvf"]).unwrap_err().unwrap_stderr();
+    assert_eq!(r, "`-f` requires an argument `FILE`");
+}
+
+#[test]
+fn then_parse_splits_at_double_dash() {
+    let dry_run = long("dry-run").help("Don't change anything").switch();
+    let mytool = construct!(dry_run).to_options().descr("Runs subtool");
+
+    let verbose = short('v').help("Be verbose").switch();
+    let file = positional::<String>("FILE");
+    let subtool = construct!(verbose, file)
+        .to_options()
+        .descr("Processes a file")
+        .name("subtool");
+
+    let parser = mytool.then_parse(subtool).to_options();
+
+    let r = parser
+        .run_inner(&["--dry-run", "--", "-v", "--", "--dry-run"])
+        .unwrap();
+    assert_eq!(r, (true, (true, "--dry-run".to_owned())));
+
+    // flags of one parser are not available on the other side of `--`
+    let r = parser
+        .run_inner(&["-v", "--", "Cargo.toml"])
+        .unwrap_err()
+        .unwrap_stderr();
+    assert_eq!(r, "`-v` is not expected in this context");
+
+    let r = parser
+        .run_inner(&["--", "--dry-run", "Cargo.toml"])
+        .unwrap_err()
+        .unwrap_stderr();
+    assert_eq!(r, "`--dry-run` is not expected in this context");
+
+    let r = parser
+        .run_inner(&["--dry-run"])
+        .unwrap_err()
+        .unwrap_stderr();
+    assert_eq!(r, "expected `FILE`, pass `--help` for usage information");
+
+    let r = parser.run_inner(&["--help"]).unwrap_err().unwrap_stdout();
+    let expected = "\
+Runs subtool
+
+Usage: [--dry-run]
+
+Available options:
+        --dry-run  Don't change anything
+    -h, --help     Prints help information
+";
+    assert_eq!(r, expected);
+
+    let r = parser
+        .run_inner(&["--", "--help"])
+        .unwrap_err()
+        .unwrap_stdout();
+    let expected = "\
+Processes a file
+
+Usage: subtool [-v] FILE
+
+Available options:
+    -v          Be verbose
+    -h, --help  Prints help information
+";
+    assert_eq!(r, expected);
+}
+
+#[test]
+fn at_most_one_of_items() {
+    let json = long("json").req_flag(());
+    let format = short('f').long("format").argument::<String>("FMT");
+    let width = long("width").argument::<u16>("COLS");
+    let verbose = short('v').switch();
+    let output = at_most_one_of((json, format, width));
+    let parser = construct!(output, verbose).to_options();
+
+    let r = parser.run_inner(&["-v"]).unwrap();
+    assert_eq!(r, ((None, None, None), true));
+
+    let r = parser.run_inner(&["--width", "80"]).unwrap();
+    assert_eq!(r, ((None, None, Some(80)), false));
+
+    let r = parser.run_inner(&["-f", "csv", "-v"]).unwrap();
+    assert_eq!(r, ((None, Some("csv".to_owned()), None), true));
+
+    let r = parser
+        .run_inner(&["--width", "80", "--json"])
+        .unwrap_err()
+        .unwrap_stderr();
+    assert_eq!(
+        r,
+        "only one of `--json`, `-f=FMT`, `--width=COLS` may be given"
+    );
+
+    let r = parser
+        .run_inner(&["--width", "x"])
+        .unwrap_err()
+        .unwrap_stderr();
+    assert_eq!(r, "couldn't parse `x`: invalid digit found in string");
+
+    let r = parser.run_inner(&["--help"]).unwrap_err().unwrap_stdout();
+    let expected = "\
+Usage: [--json | -f=FMT | --width=COLS] [-v]
+
+Available options:
+        --json
+    -f, --format=FMT
+        --width=COLS
+    -v
+    -h, --help        Prints help information
+";
+    assert_eq!(r, expected);
+}
+
+#[test]
+fn at_most_one_of_with_switch() {
+    // switch always produces a value so it's always present
+    let force = long("force").switch();
+    let name = long("name").argument::<String>("NAME");
+    let parser = at_most_one_of((force, name)).to_options();
+
+    let r = parser.run_inner(&[]).unwrap();
+    assert_eq!(r, (Some(false), None));
+
+    let r = parser.run_inner(&["--force"]).unwrap();
+    assert_eq!(r, (Some(true), None));
+
+    let r = parser
+        .run_inner(&["--name", "x"])
+        .unwrap_err()
+        .unwrap_stderr();
+    assert_eq!(r, "only one of `--force`, `--name=NAME` may be given");
+}
+
+#[test]
+fn at_most_one_of_with_optional() {
+    // same for optional items
+    let name = long("name").argument::<String>("NAME").optional();
+    let size = long("size").argument::<u32>("SIZE");
+    let parser = at_most_one_of((name, size)).to_options();
+
+    let r = parser.run_inner(&[]).unwrap();
+    assert_eq!(r, (Some(None), None));
+
+    let r = parser.run_inner(&["--name", "x"]).unwrap();
+    assert_eq!(r, (Some(Some("x".to_owned())), None));
+
+    let r = parser
+        .run_inner(&["--size", "3"])
+        .unwrap_err()
+        .unwrap_stderr();
+    assert_eq!(r, "only one of `--name=NAME`, `--size=SIZE` may be given");
+}